@@ -0,0 +1,41 @@
+use std::hint::black_box;
+
+use advent2020::day1::Day1;
+use advent2020::day2::Day2;
+use advent2020::day3::Day3;
+use advent2020::day4::Day4;
+use advent2020::day5::Day5;
+use advent2020::day6::Day6;
+use advent2020::day7::Day7;
+use advent2020::problem::{InputSource, Part, Solved};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+fn bench_day<T: Solved>(c: &mut Criterion) {
+    let input = InputSource::Default
+        .read_to_string(T::INPUT_FILE_PATH)
+        .unwrap();
+    c.bench_function(&format!("day {} parse", T::DAY), |b| {
+        b.iter(|| T::parse(black_box(&input)).unwrap())
+    });
+
+    let parsed = T::parse(&input).unwrap();
+    for part in Part::ALL {
+        c.bench_function(
+            &format!("day {} part {} solve", T::DAY, part.as_int()),
+            |b| b.iter(|| T::solve_part(part, black_box(&parsed)).unwrap()),
+        );
+    }
+}
+
+fn bench_solutions(c: &mut Criterion) {
+    bench_day::<Day1>(c);
+    bench_day::<Day2>(c);
+    bench_day::<Day3>(c);
+    bench_day::<Day4>(c);
+    bench_day::<Day5>(c);
+    bench_day::<Day6>(c);
+    bench_day::<Day7>(c);
+}
+
+criterion_group!(benches, bench_solutions);
+criterion_main!(benches);
@@ -0,0 +1,94 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use anyhow::{ensure, Result};
+use serde::{Serialize, Serializer};
+
+use crate::problem::{InputSource, Part, Solved};
+use crate::registry::Solver;
+
+/// Timings for one part of one day, split into reading the input, parsing it
+/// and solving it.
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub day:        u8,
+    pub part:       u8,
+    pub iterations: usize,
+    pub read:       Stats,
+    pub parse:      Stats,
+    pub solve:      Stats,
+}
+
+/// Summary of a set of timings; serialized as nanoseconds.
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    #[serde(rename = "min_ns", serialize_with = "as_nanos")]
+    pub min:    Duration,
+    #[serde(rename = "median_ns", serialize_with = "as_nanos")]
+    pub median: Duration,
+    #[serde(rename = "max_ns", serialize_with = "as_nanos")]
+    pub max:    Duration,
+}
+
+impl Stats {
+    fn new(mut timings: Vec<Duration>) -> Self {
+        timings.sort_unstable();
+        Self {
+            min:    timings[0],
+            median: timings[timings.len() / 2],
+            max:    timings[timings.len() - 1],
+        }
+    }
+}
+
+fn as_nanos<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(duration.as_nanos())
+}
+
+/// Runs one part of a day `iterations` times against its checked-in input.
+pub fn bench_solver(solver: &Solver, part: Part, iterations: usize) -> Result<BenchReport> {
+    ensure!(iterations > 0, "Must bench at least one iteration");
+
+    let mut read_timings = Vec::with_capacity(iterations);
+    let mut parse_timings = Vec::with_capacity(iterations);
+    let mut solve_timings = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let input = InputSource::Default.read_to_string(solver.input_file_path)?;
+        read_timings.push(start.elapsed());
+
+        let timings = (solver.time_phases)(part, &input)?;
+        parse_timings.push(timings.parse);
+        solve_timings.push(timings.solve);
+    }
+
+    Ok(BenchReport {
+        day: solver.day,
+        part: part.as_int(),
+        iterations,
+        read: Stats::new(read_timings),
+        parse: Stats::new(parse_timings),
+        solve: Stats::new(solve_timings),
+    })
+}
+
+/// How long one run took to parse its input, and to solve from the parsed
+/// input.
+#[derive(Debug)]
+pub struct PhaseTimings {
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+/// Times parsing and solving one part of a day, separately.
+pub fn time_phases<T: Solved>(part: Part, input: &str) -> Result<PhaseTimings> {
+    let start = Instant::now();
+    let parsed = black_box(T::parse(black_box(input))?);
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    black_box(T::solve_part(part, &parsed)?);
+    let solve = start.elapsed();
+
+    Ok(PhaseTimings { parse, solve })
+}
@@ -1,116 +1,407 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::iter;
+use std::ops::Range;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 
-use crate::problem::{Part, Solved};
+use crate::problem::{checked_product, Answer, Solved};
 
-// TODO(dkimbel): POTENTIAL IMPROVEMENTS
-//   - create a custom iterator that can take an arbitrary number of input vecs
-//     and return a vec (or ideally array) of their combined values; then use
-//     that to solve parts one and two using the same code
-
-const INPUT_FILE_PATH: &str = "src/day1/puzzle_inputs";
+const INPUT_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day1/puzzle_inputs");
 
+/// Day 1: Report Repair
 pub struct Day1;
 
-impl Day1 {
-    fn solve(problem_part: Part, input_file_path: &str) -> Result<u32> {
-        let analyzer = ExpenseAnalyzer::new(input_file_path)?;
+impl Solved for Day1 {
+    type Input<'a> = ExpenseAnalyzer;
 
-        let solution = match problem_part {
-            Part::One => {
-                let (expense_1, expense_2) = analyzer.find_summing_pair(2020)?;
-                expense_1 * expense_2
-            },
-            Part::Two => {
-                let (expense_1, expense_2, expense_3) = analyzer.find_summing_triple(2020)?;
-                expense_1 * expense_2 * expense_3
-            },
-        };
-        Ok(solution)
+    const DAY: u8 = 1;
+    const INPUT_FILE_PATH: &'static str = INPUT_FILE_PATH;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        ExpenseAnalyzer::new(input)
     }
-}
 
-impl Solved for Day1 {
-    fn print_solution(part: Part) {
-        let solution = Self::solve(part, INPUT_FILE_PATH).unwrap();
-        println!("Day 1 {} solution: {}", part, solution);
+    fn part_one(analyzer: &Self::Input<'_>) -> Result<Answer> {
+        let expenses = analyzer.find_summing_combination(2, 2020)?;
+        let product = checked_product(expenses.iter().map(|&expense| u64::from(expense)))?;
+        Ok(Answer::from(product))
+    }
+
+    fn part_two(analyzer: &Self::Input<'_>) -> Result<Answer> {
+        let expenses = analyzer.find_summing_combination(3, 2020)?;
+        let product = checked_product(expenses.iter().map(|&expense| u64::from(expense)))?;
+        Ok(Answer::from(product))
     }
 }
 
+/// An expense report, searchable for entries that sum to a target.
 #[derive(Debug)]
-struct ExpenseAnalyzer {
+pub struct ExpenseAnalyzer {
     expenses: Vec<u32>,
 }
 
 impl ExpenseAnalyzer {
-    fn new(file_path: &str) -> Result<Self> {
-        let file = File::open(file_path)?;
-        let reader = BufReader::new(file);
-
+    /// Parses an expense report with one integer expense per line.
+    pub fn new(input: &str) -> Result<Self> {
         let mut expenses = Vec::new();
-        for line in reader.lines() {
-            let expense: u32 = line?.parse()?;
+        for line in input.lines() {
+            let expense: u32 = line.parse()?;
             expenses.push(expense);
         }
 
         Ok(Self { expenses })
     }
 
-    fn find_summing_pair(&self, target_sum: u32) -> Result<(u32, u32)> {
-        let num_expenses = self.expenses.len();
-        for i in 0..num_expenses {
-            for j in (i + 1)..num_expenses {
-                let expense_i = self.expenses[i];
-                let expense_j = self.expenses[j];
-                if expense_i + expense_j == target_sum {
-                    return Ok((expense_i, expense_j));
+    /// Finds `k` distinct entries (by position, not value) that sum to
+    /// `target_sum`, returning their values in ascending order. Pairs are found
+    /// with a hash set in O(n); larger combinations by sorting and then walking
+    /// two pointers inward, in O(n^(k-1)).
+    pub fn find_summing_combination(&self, k: usize, target_sum: u32) -> Result<Vec<u32>> {
+        let combination = match k {
+            0 => return Err(anyhow!("Must search for at least one expense")),
+            1 => self
+                .expenses
+                .iter()
+                .find(|&&expense| expense == target_sum)
+                .map(|&e| vec![e]),
+            2 => Self::find_summing_pair(&self.expenses, target_sum),
+            _ => {
+                let mut sorted = self.expenses.clone();
+                sorted.sort_unstable();
+                Self::find_sorted_combination(&sorted, k, u64::from(target_sum))
+            },
+        };
+
+        let mut combination = combination.context(anyhow!(
+            "Could not find a set of {} expenses summing to {}",
+            k,
+            target_sum
+        ))?;
+        combination.sort_unstable();
+        Ok(combination)
+    }
+
+    /// Lazily enumerates every set of `k` distinct entries that sum to
+    /// `target_sum`, so callers can stop as soon as they've seen enough.
+    /// Entries with equal values are still distinct, so each set of line
+    /// indices is yielded exactly once.
+    pub fn summing_combinations(&self, k: usize, target_sum: u32) -> Result<SummingCombinations> {
+        if k == 0 {
+            return Err(anyhow!("Must search for at least one expense"));
+        }
+        Ok(SummingCombinations::new(&self.expenses, k, target_sum))
+    }
+
+    fn find_summing_pair(expenses: &[u32], target_sum: u32) -> Option<Vec<u32>> {
+        // only look back at expenses we've already passed, so that an entry can't
+        // be paired with itself
+        let mut seen = HashSet::new();
+        for &expense in expenses {
+            if let Some(complement) = target_sum.checked_sub(expense) {
+                if seen.contains(&complement) {
+                    return Some(vec![complement, expense]);
                 }
             }
+            seen.insert(expense);
         }
-        Err(anyhow!(
-            "Could not find a pair of expenses summing to {}",
-            target_sum
-        ))
-    }
-
-    fn find_summing_triple(&self, target_sum: u32) -> Result<(u32, u32, u32)> {
-        let num_expenses = self.expenses.len();
-        for i in 0..num_expenses {
-            for j in (i + 1)..num_expenses {
-                for k in (j + 1)..num_expenses {
-                    let expense_i = self.expenses[i];
-                    let expense_j = self.expenses[j];
-                    let expense_k = self.expenses[k];
-                    if expense_i + expense_j + expense_k == target_sum {
-                        return Ok((expense_i, expense_j, expense_k));
-                    }
+        None
+    }
+
+    // expects `sorted` in ascending order; sums are widened to u64 so they can't
+    // overflow
+    fn find_sorted_combination(sorted: &[u32], k: usize, target_sum: u64) -> Option<Vec<u32>> {
+        if k == 2 {
+            return Self::find_sorted_pair(sorted, target_sum);
+        }
+
+        for (i, &expense) in sorted.iter().enumerate() {
+            // later expenses are at least this big, so the smallest possible sum
+            // from here on is k of this one
+            if u64::from(expense).saturating_mul(k as u64) > target_sum {
+                break;
+            }
+            // trying the same value twice as the smallest entry can't find anything new
+            if i > 0 && sorted[i - 1] == expense {
+                continue;
+            }
+            let remaining_sum = target_sum - u64::from(expense);
+            if let Some(mut rest) =
+                Self::find_sorted_combination(&sorted[i + 1..], k - 1, remaining_sum)
+            {
+                rest.push(expense);
+                return Some(rest);
+            }
+        }
+        None
+    }
+
+    fn find_sorted_pair(sorted: &[u32], target_sum: u64) -> Option<Vec<u32>> {
+        if sorted.len() < 2 {
+            return None;
+        }
+        let mut low = 0;
+        let mut high = sorted.len() - 1;
+        while low < high {
+            let sum = u64::from(sorted[low]) + u64::from(sorted[high]);
+            match sum.cmp(&target_sum) {
+                Ordering::Equal => return Some(vec![sorted[low], sorted[high]]),
+                Ordering::Less => low += 1,
+                Ordering::Greater => high -= 1,
+            }
+        }
+        None
+    }
+}
+
+/// One entry of an expense report, identified by its zero-based line index.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Expense {
+    pub index: usize,
+    pub value: u32,
+}
+
+/// Iterator over every combination of expenses summing to a target; see
+/// [`ExpenseAnalyzer::summing_combinations`]. Each combination is ordered by
+/// line index.
+pub struct SummingCombinations {
+    // ascending by value, so that a too-large partial sum rules out every later
+    // entry too
+    sorted:     Vec<Expense>,
+    k:          usize,
+    target_sum: u64,
+    // positions in `sorted` of all but the last entry of the current
+    // combination, strictly increasing
+    chosen:     Vec<usize>,
+    chosen_sum: u64,
+    // positions in `sorted` that complete the current combination
+    matches:    Range<usize>,
+    started:    bool,
+}
+
+impl SummingCombinations {
+    fn new(expenses: &[u32], k: usize, target_sum: u32) -> Self {
+        let mut sorted = expenses
+            .iter()
+            .enumerate()
+            .map(|(index, &value)| Expense { index, value })
+            .collect::<Vec<_>>();
+        sorted.sort_unstable_by_key(|expense| (expense.value, expense.index));
+
+        // with fewer than k entries there's nothing to yield, so start out
+        // exhausted rather than reserving room for k of them
+        let exhausted = k > sorted.len();
+        Self {
+            chosen: Vec::with_capacity(k.min(sorted.len())),
+            sorted,
+            k,
+            target_sum: u64::from(target_sum),
+            chosen_sum: 0,
+            matches: 0..0,
+            started: exhausted,
+        }
+    }
+
+    // Moves on to the next set of k - 1 entries that could still be completed,
+    // and finds the entries that would complete it. Returns false once every
+    // set has been tried.
+    fn advance(&mut self) -> bool {
+        let mut next_pos = if self.started {
+            match self.pop() {
+                Some(pos) => pos + 1,
+                None => return false,
+            }
+        } else {
+            self.started = true;
+            0
+        };
+
+        loop {
+            if self.chosen.len() == self.k - 1 {
+                self.matches = self.find_matches(next_pos);
+                return true;
+            }
+            // every entry from here on is at least this big
+            let num_remaining = (self.k - self.chosen.len()) as u64;
+            let fits = self.sorted.get(next_pos).is_some_and(|expense| {
+                let smallest_rest = u64::from(expense.value).saturating_mul(num_remaining);
+                self.chosen_sum.saturating_add(smallest_rest) <= self.target_sum
+            });
+            if fits {
+                self.chosen_sum += u64::from(self.sorted[next_pos].value);
+                self.chosen.push(next_pos);
+                next_pos += 1;
+            } else {
+                match self.pop() {
+                    Some(pos) => next_pos = pos + 1,
+                    None => return false,
                 }
             }
         }
-        Err(anyhow!(
-            "Could not find a set of expenses summing to {}",
-            target_sum
-        ))
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        let pos = self.chosen.pop()?;
+        self.chosen_sum -= u64::from(self.sorted[pos].value);
+        Some(pos)
+    }
+
+    // positions from `start` onward whose value brings the sum to exactly the
+    // target
+    fn find_matches(&self, start: usize) -> Range<usize> {
+        let needed = match self.target_sum.checked_sub(self.chosen_sum) {
+            Some(needed) => needed,
+            None => return 0..0,
+        };
+        let candidates = &self.sorted[start.min(self.sorted.len())..];
+        let low = candidates.partition_point(|expense| u64::from(expense.value) < needed);
+        let high = candidates.partition_point(|expense| u64::from(expense.value) <= needed);
+        (start + low)..(start + high)
+    }
+}
+
+impl Iterator for SummingCombinations {
+    type Item = Vec<Expense>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(last_pos) = self.matches.next() {
+                let mut combination = self
+                    .chosen
+                    .iter()
+                    .chain(iter::once(&last_pos))
+                    .map(|&pos| self.sorted[pos])
+                    .collect::<Vec<_>>();
+                combination.sort_unstable_by_key(|expense| expense.index);
+                return Some(combination);
+            }
+            if !self.advance() {
+                return None;
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
+    use crate::problem::{ArithmeticError, Part};
 
     const TEST_FILE_PATH: &str = "src/day1/sample";
 
     #[test]
     fn test_part_one() {
-        let solution = Day1::solve(Part::One, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 514579);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day1::solution(Part::One, &input).unwrap();
+        assert_eq!(solution, Answer::Int(514579));
     }
 
     #[test]
     fn test_part_two() {
-        let solution = Day1::solve(Part::Two, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 241861950);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day1::solution(Part::Two, &input).unwrap();
+        assert_eq!(solution, Answer::Int(241861950));
+    }
+
+    #[test]
+    fn test_combination_sizes() {
+        let analyzer = ExpenseAnalyzer::new("1721\n979\n366\n299\n675\n1456").unwrap();
+        assert_eq!(analyzer.find_summing_combination(1, 979).unwrap(), vec![
+            979
+        ]);
+        assert_eq!(analyzer.find_summing_combination(2, 2020).unwrap(), vec![
+            299, 1721
+        ]);
+        assert_eq!(analyzer.find_summing_combination(3, 2020).unwrap(), vec![
+            366, 675, 979
+        ]);
+        assert_eq!(analyzer.find_summing_combination(4, 3365).unwrap(), vec![
+            299, 366, 979, 1721
+        ]);
+    }
+
+    #[test]
+    fn test_combination_reuses_no_entry() {
+        let analyzer = ExpenseAnalyzer::new("1010\n5\n7").unwrap();
+        assert!(analyzer.find_summing_combination(2, 2020).is_err());
+        assert!(analyzer.find_summing_combination(3, 3030).is_err());
+
+        let analyzer = ExpenseAnalyzer::new("1010\n5\n1010").unwrap();
+        assert_eq!(analyzer.find_summing_combination(2, 2020).unwrap(), vec![
+            1010, 1010
+        ]);
+        assert_eq!(analyzer.find_summing_combination(3, 2025).unwrap(), vec![
+            5, 1010, 1010
+        ]);
+        assert!(analyzer.find_summing_combination(0, 0).is_err());
+        assert!(analyzer.find_summing_combination(4, 2025).is_err());
+    }
+
+    #[test]
+    fn test_all_combinations() {
+        let analyzer = ExpenseAnalyzer::new("1010\n5\n1010\n1015\n1005\n2020").unwrap();
+        let mut pairs = analyzer
+            .summing_combinations(2, 2020)
+            .unwrap()
+            .map(|combination| combination.iter().map(|e| e.index).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        pairs.sort();
+        assert_eq!(pairs, vec![vec![0, 2], vec![3, 4]]);
+
+        let singles = analyzer
+            .summing_combinations(1, 2020)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(singles, vec![vec![Expense { index: 5, value: 2020 }]]);
+
+        // 5 + 1005 + 1010 twice over, once for each 1010
+        assert_eq!(analyzer.summing_combinations(3, 2020).unwrap().count(), 2);
+        assert_eq!(analyzer.summing_combinations(6, 2020).unwrap().count(), 0);
+        assert!(analyzer.summing_combinations(0, 2020).is_err());
+        // far more entries than the report has, which mustn't try to reserve
+        // room for all of them
+        assert_eq!(
+            analyzer
+                .summing_combinations(1 << 40, 2020)
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_combinations_stop_early() {
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let analyzer = ExpenseAnalyzer::new(&input).unwrap();
+        let first = analyzer
+            .summing_combinations(3, 2020)
+            .unwrap()
+            .next()
+            .unwrap();
+        let values = first.iter().map(|e| e.value).collect::<Vec<_>>();
+        assert_eq!(values, vec![979, 366, 675]);
+        assert_eq!(first.iter().map(|e| e.index).collect::<Vec<_>>(), vec![
+            1, 2, 4
+        ]);
+    }
+
+    #[test]
+    fn test_product_overflow() {
+        // 3 * 1431655765 is u32::MAX, but their product doesn't fit in a u64
+        let input = "1431655765\n1431655765\n1431655765";
+        let analyzer = ExpenseAnalyzer::new(input).unwrap();
+        let expenses = analyzer.find_summing_combination(3, u32::MAX).unwrap();
+        let product = checked_product(expenses.iter().map(|&expense| u64::from(expense)));
+        assert_eq!(
+            product,
+            Err(ArithmeticError::Overflow { values: vec![1431655765; 3] })
+        );
+
+        // pairs of u32s always fit once widened
+        let expenses = analyzer.find_summing_combination(2, 2863311530).unwrap();
+        let product = checked_product(expenses.iter().map(|&expense| u64::from(expense)));
+        assert_eq!(product, Ok(2049638229457735225));
     }
 }
@@ -1,150 +1,929 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::problem::{Part, Solved};
+use crate::problem::{Answer, Part, Solved};
 
-// TODO(dkimbel): POTENTIAL IMPROVEMENTS
-//   - Reduce boilerplate shared between Day modules. Is a trait the key? At the
-//     worst, create a 'template' file I can `cp` to make a new Day, and add it
-//     to README.
-
-const INPUT_FILE_PATH: &str = "src/day2/puzzle_inputs";
+const INPUT_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day2/puzzle_inputs");
 
+/// Day 2: Password Philosophy
 pub struct Day2;
 
+impl Solved for Day2 {
+    type Input<'a> = PasswordDatabase;
+
+    const DAY: u8 = 2;
+    const INPUT_FILE_PATH: &'static str = INPUT_FILE_PATH;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        PasswordDatabase::parse(input, ParseMode::Strict)
+    }
+
+    fn part_one(database: &Self::Input<'_>) -> Result<Answer> {
+        Self::count_valid(database, Part::One)
+    }
+
+    fn part_two(database: &Self::Input<'_>) -> Result<Answer> {
+        Self::count_valid(database, Part::Two)
+    }
+}
+
 impl Day2 {
-    fn solve(problem_part: Part, input_file_path: &str) -> Result<u32> {
-        let file = File::open(input_file_path)?;
-        let reader = BufReader::new(file);
-        let mut solution = 0;
-        for line in reader.lines() {
-            let validator = PasswordValidator::new(&line?)?;
-            if validator.is_valid(problem_part)? {
+    fn count_valid(database: &PasswordDatabase, problem_part: Part) -> Result<Answer> {
+        let mut solution: u32 = 0;
+        for (_, validator) in database.entries() {
+            if validator.is_valid(problem_part, TextOptions::default())? {
                 solution += 1;
             }
         }
-        Ok(solution)
+        Ok(Answer::from(solution))
+    }
+
+    /// Reports every password that fails its policy under the given part's
+    /// rules, labelled with the line it was parsed from.
+    pub fn violations(
+        database: &PasswordDatabase,
+        problem_part: Part,
+        text: TextOptions,
+    ) -> Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        for (line, validator) in database.entries() {
+            let violation = validator
+                .check(*line, problem_part, text)
+                .context(anyhow!("Failed to check line {}", line))?;
+            if let Some(violation) = violation {
+                violations.push(violation);
+            }
+        }
+        Ok(violations)
     }
 }
 
-impl Solved for Day2 {
-    fn print_solution(part: Part) {
-        let solution = Self::solve(part, INPUT_FILE_PATH).unwrap();
-        println!("Day 2 {} solution: {}", part, solution);
+/// How to handle malformed lines in a password database.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseMode {
+    /// Fail on the first malformed line.
+    Strict,
+    /// Skip malformed lines, collecting their errors.
+    Lenient,
+}
+
+/// The validators parsed from a password database, each with its one-based
+/// line number, plus any lines skipped in lenient mode.
+pub struct PasswordDatabase {
+    entries: Vec<(usize, PasswordValidator)>,
+    errors:  Vec<ParseError>,
+}
+
+impl PasswordDatabase {
+    pub fn parse(input: &str, mode: ParseMode) -> Result<Self> {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        for (index, line_input) in input.lines().enumerate() {
+            let line = index + 1;
+            match PasswordValidator::parse_line(line_input) {
+                Ok(validator) => entries.push((line, validator)),
+                Err((column, message)) => {
+                    let error = ParseError { line, column, message };
+                    match mode {
+                        ParseMode::Strict => return Err(anyhow!(error)),
+                        ParseMode::Lenient => errors.push(error),
+                    }
+                },
+            }
+        }
+        Ok(Self { entries, errors })
+    }
+
+    pub fn entries(&self) -> &[(usize, PasswordValidator)] {
+        &self.entries
+    }
+
+    /// The malformed lines skipped in lenient mode; always empty in strict
+    /// mode.
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+}
+
+/// A malformed line of the password database, with one-based line and column
+/// numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line:    usize,
+    pub column:  usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// a one-based column within a line, plus what went wrong there
+type ColumnError = (usize, String);
+
+fn column_after(input: &str) -> usize {
+    input.chars().count() + 1
+}
+
+/// Why one line of the password database is invalid.
+#[derive(Debug, Serialize)]
+pub struct Violation {
+    pub line:     usize,
+    pub policy:   String,
+    pub password: String,
+    pub rule:     String,
+    pub reason:   String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}: {}: {} fails {} ({})",
+            self.line, self.policy, self.password, self.rule, self.reason
+        )
+    }
+}
+
+/// How passwords and policy characters are compared. The defaults match the
+/// puzzle: positions count code points, and nothing is normalized.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct TextOptions {
+    pub positions:     PositionUnit,
+    pub normalization: Normalization,
+}
+
+/// What a single position (or counted occurrence) in a password is.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum PositionUnit {
+    #[default]
+    CodePoint,
+    /// An extended grapheme cluster, so `e` plus a combining accent, or an
+    /// emoji with modifiers, is one position.
+    Grapheme,
+}
+
+/// Unicode normalization applied to both passwords and policy characters
+/// before comparing them.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Normalization {
+    #[default]
+    None,
+    Nfc,
+    Nfkc,
+}
+
+impl FromStr for Normalization {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "nfc" => Ok(Self::Nfc),
+            "nfkc" => Ok(Self::Nfkc),
+            _ => Err(anyhow!(
+                "Invalid normalization {}; expected none, nfc or nfkc",
+                s
+            )),
+        }
+    }
+}
+
+impl TextOptions {
+    fn normalize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        match self.normalization {
+            Normalization::None => Cow::Borrowed(text),
+            Normalization::Nfc => Cow::Owned(text.nfc().collect()),
+            Normalization::Nfkc => Cow::Owned(text.nfkc().collect()),
+        }
+    }
+
+    // the single position a policy character stands for: its first code point,
+    // or its whole first grapheme, once normalized
+    fn policy_unit(&self, character: &str) -> String {
+        let character = self.normalize(character);
+        let unit = self.units(&character).first().copied().unwrap_or_default();
+        String::from(unit)
+    }
+
+    // splits already-normalized text into positions
+    fn units<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        match self.positions {
+            PositionUnit::CodePoint => text
+                .char_indices()
+                .map(|(index, char)| &text[index..index + char.len_utf8()])
+                .collect(),
+            PositionUnit::Grapheme => text.graphemes(true).collect(),
+        }
     }
 }
 
-struct PasswordValidator {
+/// A password paired with the policy it must satisfy.
+pub struct PasswordValidator {
     policy:   PasswordPolicy,
     password: String,
 }
 
 impl PasswordValidator {
-    // expects a string like "1-3 a: abcde"
-    fn new(input: &str) -> Result<Self> {
-        let (policy_input, password) = input
-            .split_once(": ")
-            .context("Failed to split validator input into policy and password")?;
-        let policy = PasswordPolicy::new(policy_input)?;
+    /// Parses a line like `1-3 a: abcde`.
+    pub fn new(input: &str) -> Result<Self> {
+        Self::parse_line(input)
+            .map_err(|(column, message)| anyhow!("column {}: {}", column, message))
+    }
+
+    fn parse_line(input: &str) -> Result<Self, ColumnError> {
+        let (policy_input, password) = input.split_once(": ").ok_or_else(|| {
+            (
+                column_after(input),
+                String::from("missing \": \" between policy and password"),
+            )
+        })?;
+        let policy = PasswordPolicy::parse(policy_input)?;
         Ok(Self {
             policy,
             password: String::from(password),
         })
     }
 
-    fn is_valid(&self, problem_part: Part) -> Result<bool> {
-        self.policy.allows_password(&self.password, problem_part)
+    /// Checks the password against its policy, using the rules of the given
+    /// part.
+    pub fn is_valid(&self, problem_part: Part, text: TextOptions) -> Result<bool> {
+        self.policy.rule(problem_part, text).allows(&self.password)
+    }
+
+    /// Like `is_valid`, but explains the failure; `line` is only used to label
+    /// the report.
+    pub fn check(
+        &self,
+        line: usize,
+        problem_part: Part,
+        text: TextOptions,
+    ) -> Result<Option<Violation>> {
+        let violation = self
+            .policy
+            .rule(problem_part, text)
+            .violation(&self.password)?;
+        Ok(violation.map(|violation| Violation {
+            line,
+            policy: self.policy.to_string(),
+            password: self.password.clone(),
+            rule: violation.rule,
+            reason: violation.reason,
+        }))
+    }
+
+    /// Checks the password against an arbitrary rule instead of its own policy.
+    pub fn satisfies(&self, rule: &dyn PasswordRule) -> Result<bool> {
+        rule.allows(&self.password)
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
     }
 }
 
-struct PasswordPolicy {
-    character:       char,
+/// A character plus two numbers, interpreted differently by each part.
+pub struct PasswordPolicy {
+    // a whole grapheme cluster, so it can match grapheme positions; `rule`
+    // narrows it to its first code point otherwise
+    character:       String,
     lower_range_num: usize,
     upper_range_num: usize,
 }
 
 impl PasswordPolicy {
-    // expects a string like "1-3 a"
-    fn new(input: &str) -> Result<Self> {
-        let (range_input, char_input) = input
-            .split_once(" ")
-            .context("Failed to split policy input into range and character")?;
+    /// Parses a policy like `1-3 a`.
+    pub fn new(input: &str) -> Result<Self> {
+        Self::parse(input).map_err(|(column, message)| anyhow!("column {}: {}", column, message))
+    }
+
+    fn parse(input: &str) -> Result<Self, ColumnError> {
+        let (range_input, char_input) = input.split_once(' ').ok_or_else(|| {
+            (
+                column_after(input),
+                String::from("missing space before policy character"),
+            )
+        })?;
         let (lower_range_input, upper_range_input) = range_input
-            .split_once("-")
-            .context("Failed to split range input into min and max")?;
+            .split_once('-')
+            .ok_or_else(|| (1, format!("range {:?} is missing \"-\"", range_input)))?;
+        let lower_range_num = lower_range_input
+            .parse::<usize>()
+            .map_err(|err| (1, format!("lower bound {:?}: {}", lower_range_input, err)))?;
+        let upper_range_num = upper_range_input.parse::<usize>().map_err(|err| {
+            let column = column_after(lower_range_input) + 1;
+            (
+                column,
+                format!("upper bound {:?}: {}", upper_range_input, err),
+            )
+        })?;
+        let character = char_input
+            .graphemes(true)
+            .next()
+            .map(String::from)
+            .ok_or_else(|| {
+                (
+                    column_after(range_input) + 1,
+                    String::from("missing policy character"),
+                )
+            })?;
         Ok(Self {
-            lower_range_num: lower_range_input.parse::<usize>()?,
-            upper_range_num: upper_range_input.parse::<usize>()?,
-            character:       char_input
-                .chars()
-                .next()
-                .context("Failed to extract char from policy input")?,
+            lower_range_num,
+            upper_range_num,
+            character,
         })
     }
 
-    fn allows_password(&self, password: &str, problem_part: Part) -> Result<bool> {
+    /// Part one treats the numbers as an allowed range of occurrences; part two
+    /// as one-based positions, exactly one of which must hold the character.
+    pub fn rule(&self, problem_part: Part, text: TextOptions) -> Box<dyn PasswordRule> {
+        let character = text.policy_unit(&self.character);
         match problem_part {
-            Part::One => {
-                // In part one, we treat the policy's upper_range_num as the max allowable
-                // number of occurrences of the character. Similarly, we treat lower_range_num
-                // as the min allowable number.
-                let char_occurrences =
-                    password.chars().fold(
-                        0,
-                        |acc, char| if char == self.character { acc + 1 } else { acc },
-                    );
-                Ok(char_occurrences <= self.upper_range_num
-                    && char_occurrences >= self.lower_range_num)
-            },
-            Part::Two => {
-                // In part two, we require that the character number specified by the lower num
-                // XOR the character number specified by the upper num must equal the given
-                // char. Note that these are one-based indexes, not zero-based.
-                let chars = password.chars().collect::<Vec<_>>();
-                let first_char_match = chars
-                    .get(self.lower_range_index()?)
-                    .context("Password must contain lower char number specified by policy")?
-                    == &self.character;
-                let second_char_match = chars
-                    .get(self.upper_range_index()?)
-                    .context("Password must contain upper char number specified by policy")?
-                    == &self.character;
-                Ok((first_char_match && !second_char_match)
-                    || (!first_char_match && second_char_match))
-            },
-        }
-    }
-
-    fn lower_range_index(&self) -> Result<usize> {
-        Self::convert_to_index(self.lower_range_num)
-    }
-
-    fn upper_range_index(&self) -> Result<usize> {
-        Self::convert_to_index(self.upper_range_num)
-    }
-
-    fn convert_to_index(num: usize) -> Result<usize> {
-        num.checked_sub(1).context("Subtraction from usize failed")
+            Part::One => Box::new(rules::CountRange {
+                character,
+                min: self.lower_range_num,
+                max: self.upper_range_num,
+                text,
+            }),
+            Part::Two => Box::new(rules::PositionalXor {
+                character,
+                first: self.lower_range_num,
+                second: self.upper_range_num,
+                text,
+            }),
+        }
+    }
+
+    pub fn allows_password(&self, password: &str, problem_part: Part) -> Result<bool> {
+        self.rule(problem_part, TextOptions::default())
+            .allows(password)
+    }
+}
+
+impl fmt::Display for PasswordPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} {}",
+            self.lower_range_num, self.upper_range_num, self.character
+        )
+    }
+}
+
+/// A check that a password either passes or fails. Rules can be combined with
+/// [`rules::And`], [`rules::Or`] and [`rules::Not`].
+pub trait PasswordRule {
+    fn allows(&self, password: &str) -> Result<bool>;
+
+    /// Short description for reports, like `count of 'a'`.
+    fn name(&self) -> String;
+
+    /// Explains why the password fails this rule, or returns `None` if it
+    /// passes.
+    fn violation(&self, password: &str) -> Result<Option<RuleViolation>> {
+        if self.allows(password)? {
+            return Ok(None);
+        }
+        Ok(Some(RuleViolation {
+            rule:   self.name(),
+            reason: String::from("not satisfied"),
+        }))
+    }
+}
+
+/// The rule a password failed, and why.
+#[derive(Debug)]
+pub struct RuleViolation {
+    pub rule:   String,
+    pub reason: String,
+}
+
+/// The puzzle's two policy rules, plus general-purpose ones for validating
+/// other password databases.
+pub mod rules {
+    use anyhow::{Context, Result};
+    use regex::Regex;
+
+    use super::{PasswordRule, RuleViolation, TextOptions};
+
+    /// The character must occur between `min` and `max` times, inclusive.
+    pub struct CountRange {
+        pub character: String,
+        pub min:       usize,
+        pub max:       usize,
+        pub text:      TextOptions,
+    }
+
+    impl CountRange {
+        fn count_occurrences(&self, password: &str) -> usize {
+            let character = self.text.normalize(&self.character);
+            let password = self.text.normalize(password);
+            self.text
+                .units(&password)
+                .into_iter()
+                .filter(|&unit| unit == character)
+                .count()
+        }
+    }
+
+    impl PasswordRule for CountRange {
+        fn allows(&self, password: &str) -> Result<bool> {
+            let char_occurrences = self.count_occurrences(password);
+            Ok(char_occurrences <= self.max && char_occurrences >= self.min)
+        }
+
+        fn name(&self) -> String {
+            format!("count of '{}'", self.character)
+        }
+
+        fn violation(&self, password: &str) -> Result<Option<RuleViolation>> {
+            if self.allows(password)? {
+                return Ok(None);
+            }
+            let char_occurrences = self.count_occurrences(password);
+            Ok(Some(RuleViolation {
+                rule:   self.name(),
+                reason: format!(
+                    "found {} '{}', allowed {}\u{2013}{}",
+                    char_occurrences, self.character, self.min, self.max
+                ),
+            }))
+        }
+    }
+
+    /// Exactly one of the characters at one-based positions `first` and
+    /// `second` must be the given character. A password too short to have both
+    /// positions, or a position of 0, fails the rule.
+    pub struct PositionalXor {
+        pub character: String,
+        pub first:     usize,
+        pub second:    usize,
+        pub text:      TextOptions,
+    }
+
+    impl PositionalXor {
+        // whether the first and second positions hold the character, or why the
+        // password doesn't have both positions
+        fn matches(&self, password: &str) -> Result<(bool, bool), String> {
+            if self.first == 0 || self.second == 0 {
+                return Err(String::from(
+                    "position 0 does not exist; positions are one-based",
+                ));
+            }
+            let character = self.text.normalize(&self.character);
+            let password = self.text.normalize(password);
+            let units = self.text.units(&password);
+            match (units.get(self.first - 1), units.get(self.second - 1)) {
+                (Some(first), Some(second)) => Ok((*first == character, *second == character)),
+                _ => Err(format!(
+                    "password has only {} positions, policy needs {}",
+                    units.len(),
+                    self.first.max(self.second)
+                )),
+            }
+        }
+    }
+
+    impl PasswordRule for PositionalXor {
+        fn allows(&self, password: &str) -> Result<bool> {
+            Ok(self
+                .matches(password)
+                .is_ok_and(|(first_char_match, second_char_match)| {
+                    first_char_match != second_char_match
+                }))
+        }
+
+        fn name(&self) -> String {
+            format!(
+                "'{}' at exactly one of positions {} and {}",
+                self.character, self.first, self.second
+            )
+        }
+
+        fn violation(&self, password: &str) -> Result<Option<RuleViolation>> {
+            let reason = match self.matches(password) {
+                Ok((true, true)) => format!("found '{}' at both positions", self.character),
+                Ok((false, false)) => format!("found '{}' at neither position", self.character),
+                Ok(_) => return Ok(None),
+                Err(reason) => reason,
+            };
+            Ok(Some(RuleViolation { rule: self.name(), reason }))
+        }
+    }
+
+    /// Some part of the password must match the regex; anchor it with `^...$`
+    /// to match the whole password.
+    pub struct RegexMatch(pub Regex);
+
+    impl RegexMatch {
+        pub fn new(pattern: &str) -> Result<Self> {
+            let regex = Regex::new(pattern).context("Failed to build Regex pattern")?;
+            Ok(Self(regex))
+        }
+    }
+
+    impl PasswordRule for RegexMatch {
+        fn allows(&self, password: &str) -> Result<bool> {
+            Ok(self.0.is_match(password))
+        }
+
+        fn name(&self) -> String {
+            format!("match /{}/", self.0)
+        }
+    }
+
+    /// The password must have at least this many characters.
+    pub struct MinLength(pub usize);
+
+    impl PasswordRule for MinLength {
+        fn allows(&self, password: &str) -> Result<bool> {
+            Ok(password.chars().count() >= self.0)
+        }
+
+        fn name(&self) -> String {
+            format!("at least {} characters", self.0)
+        }
+
+        fn violation(&self, password: &str) -> Result<Option<RuleViolation>> {
+            let len = password.chars().count();
+            if len >= self.0 {
+                return Ok(None);
+            }
+            Ok(Some(RuleViolation {
+                rule:   self.name(),
+                reason: format!("found {} characters", len),
+            }))
+        }
+    }
+
+    #[derive(Debug, Copy, Clone)]
+    pub enum CharClass {
+        Lowercase,
+        Uppercase,
+        Digit,
+        // anything that isn't alphanumeric or whitespace
+        Symbol,
+    }
+
+    impl CharClass {
+        fn contains(&self, char: char) -> bool {
+            match self {
+                Self::Lowercase => char.is_lowercase(),
+                Self::Uppercase => char.is_uppercase(),
+                Self::Digit => char.is_numeric(),
+                Self::Symbol => !char.is_alphanumeric() && !char.is_whitespace(),
+            }
+        }
+    }
+
+    /// The password must contain at least one character from each class.
+    pub struct RequiredCharClasses(pub Vec<CharClass>);
+
+    impl PasswordRule for RequiredCharClasses {
+        fn allows(&self, password: &str) -> Result<bool> {
+            Ok(self
+                .0
+                .iter()
+                .all(|class| password.chars().any(|char| class.contains(char))))
+        }
+
+        fn name(&self) -> String {
+            format!("contains {:?}", self.0)
+        }
+
+        fn violation(&self, password: &str) -> Result<Option<RuleViolation>> {
+            let missing = self
+                .0
+                .iter()
+                .filter(|class| !password.chars().any(|char| class.contains(char)))
+                .collect::<Vec<_>>();
+            if missing.is_empty() {
+                return Ok(None);
+            }
+            Ok(Some(RuleViolation {
+                rule:   self.name(),
+                reason: format!("missing {:?}", missing),
+            }))
+        }
+    }
+
+    /// The password must not contain any of these substrings.
+    pub struct ForbiddenSubstrings(pub Vec<String>);
+
+    impl PasswordRule for ForbiddenSubstrings {
+        fn allows(&self, password: &str) -> Result<bool> {
+            Ok(!self
+                .0
+                .iter()
+                .any(|substring| password.contains(substring.as_str())))
+        }
+
+        fn name(&self) -> String {
+            format!("none of {:?}", self.0)
+        }
+
+        fn violation(&self, password: &str) -> Result<Option<RuleViolation>> {
+            let found = self
+                .0
+                .iter()
+                .find(|substring| password.contains(substring.as_str()));
+            Ok(found.map(|substring| RuleViolation {
+                rule:   self.name(),
+                reason: format!("found {:?}", substring),
+            }))
+        }
+    }
+
+    /// Every rule must pass.
+    pub struct And(pub Vec<Box<dyn PasswordRule>>);
+
+    impl PasswordRule for And {
+        fn allows(&self, password: &str) -> Result<bool> {
+            for rule in &self.0 {
+                if !rule.allows(password)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+
+        fn name(&self) -> String {
+            let names = self.0.iter().map(|rule| rule.name()).collect::<Vec<_>>();
+            format!("all of [{}]", names.join(", "))
+        }
+
+        // reports the first failing rule, rather than the combination
+        fn violation(&self, password: &str) -> Result<Option<RuleViolation>> {
+            for rule in &self.0 {
+                if let Some(violation) = rule.violation(password)? {
+                    return Ok(Some(violation));
+                }
+            }
+            Ok(None)
+        }
+    }
+
+    /// At least one rule must pass.
+    pub struct Or(pub Vec<Box<dyn PasswordRule>>);
+
+    impl PasswordRule for Or {
+        fn allows(&self, password: &str) -> Result<bool> {
+            for rule in &self.0 {
+                if rule.allows(password)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+
+        fn name(&self) -> String {
+            let names = self.0.iter().map(|rule| rule.name()).collect::<Vec<_>>();
+            format!("any of [{}]", names.join(", "))
+        }
+    }
+
+    /// The rule must fail.
+    pub struct Not(pub Box<dyn PasswordRule>);
+
+    impl PasswordRule for Not {
+        fn allows(&self, password: &str) -> Result<bool> {
+            Ok(!self.0.allows(password)?)
+        }
+
+        fn name(&self) -> String {
+            format!("not {}", self.0.name())
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
 
     const TEST_FILE_PATH: &str = "src/day2/sample";
 
     #[test]
     fn test_part_one() {
-        let solution = Day2::solve(Part::One, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 2);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day2::solution(Part::One, &input).unwrap();
+        assert_eq!(solution, Answer::Int(2));
     }
 
     #[test]
     fn test_part_two() {
-        let solution = Day2::solve(Part::Two, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 1);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day2::solution(Part::Two, &input).unwrap();
+        assert_eq!(solution, Answer::Int(1));
+    }
+
+    #[test]
+    fn test_puzzle_rules() {
+        let policy = PasswordPolicy::new("1-3 a").unwrap();
+        assert!(policy.allows_password("abcde", Part::One).unwrap());
+        assert!(!policy.allows_password("aaaa", Part::One).unwrap());
+        assert!(policy.allows_password("abcde", Part::Two).unwrap());
+        assert!(!policy.allows_password("abade", Part::Two).unwrap());
+        assert!(!policy.allows_password("ab", Part::Two).unwrap());
+    }
+
+    #[test]
+    fn test_general_rules() {
+        assert!(rules::MinLength(8).allows("password").unwrap());
+        assert!(!rules::MinLength(8).allows("passwrd").unwrap());
+
+        let regex = rules::RegexMatch::new(r"^[a-z]+\d$").unwrap();
+        assert!(regex.allows("hunter2").unwrap());
+        assert!(!regex.allows("hunter22").unwrap());
+
+        let classes = rules::RequiredCharClasses(vec![
+            rules::CharClass::Lowercase,
+            rules::CharClass::Uppercase,
+            rules::CharClass::Digit,
+            rules::CharClass::Symbol,
+        ]);
+        assert!(classes.allows("Hunter2!").unwrap());
+        assert!(!classes.allows("Hunter22").unwrap());
+
+        let forbidden = rules::ForbiddenSubstrings(vec![String::from("password")]);
+        assert!(!forbidden.allows("mypassword1").unwrap());
+        assert!(forbidden.allows("hunter2").unwrap());
+    }
+
+    #[test]
+    fn test_composed_rules() {
+        let corporate = rules::And(vec![
+            Box::new(rules::MinLength(8)),
+            Box::new(rules::Not(Box::new(
+                rules::RegexMatch::new("(?i)password").unwrap(),
+            ))),
+            Box::new(rules::Or(vec![
+                Box::new(rules::RequiredCharClasses(vec![rules::CharClass::Digit])),
+                Box::new(rules::RequiredCharClasses(vec![rules::CharClass::Symbol])),
+            ])),
+        ]);
+        assert!(corporate.allows("correct-horse").unwrap());
+        assert!(corporate.allows("hunter222").unwrap());
+        assert!(!corporate.allows("hunter2").unwrap());
+        assert!(!corporate.allows("Password123").unwrap());
+        assert!(!corporate.allows("correcthorse").unwrap());
+
+        let validator = PasswordValidator::new("1-3 a: abcde").unwrap();
+        assert!(!validator.satisfies(&corporate).unwrap());
+
+        let violation = corporate.violation("hunter2").unwrap().unwrap();
+        assert_eq!(violation.rule, "at least 8 characters");
+        assert_eq!(violation.reason, "found 7 characters");
+    }
+
+    #[test]
+    fn test_violations() {
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let database = Day2::parse(&input).unwrap();
+
+        let violations = Day2::violations(&database, Part::One, TextOptions::default()).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, 2);
+        assert_eq!(violations[0].policy, "1-3 b");
+        assert_eq!(violations[0].password, "cdefg");
+        assert_eq!(violations[0].reason, "found 0 'b', allowed 1\u{2013}3");
+
+        let violations = Day2::violations(&database, Part::Two, TextOptions::default()).unwrap();
+        let reasons = violations
+            .iter()
+            .map(|violation| (violation.line, violation.reason.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(reasons, vec![
+            (2, "found 'b' at neither position"),
+            (3, "found 'c' at both positions"),
+        ]);
+
+        // a password too short for its policy is a violation, not an error, and
+        // counts as invalid when solving too
+        let input = "1-3 a: abcde\n1-9 b: ab\n1-3 c: cc\n";
+        assert_eq!(Day2::solution(Part::Two, input).unwrap(), Answer::Int(1));
+        let database = Day2::parse(input).unwrap();
+        let violations = Day2::violations(&database, Part::Two, TextOptions::default()).unwrap();
+        let reasons = violations
+            .iter()
+            .map(|violation| (violation.line, violation.reason.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(reasons, vec![
+            (2, "password has only 2 positions, policy needs 9"),
+            (3, "password has only 2 positions, policy needs 3"),
+        ]);
+
+        // so does a policy naming position 0, which parses fine
+        let input = "1-3 a: abcde\n0-3 a: abc\n";
+        assert_eq!(Day2::solution(Part::Two, input).unwrap(), Answer::Int(1));
+        let database = Day2::parse(input).unwrap();
+        let violations = Day2::violations(&database, Part::Two, TextOptions::default()).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, 2);
+        assert_eq!(
+            violations[0].reason,
+            "position 0 does not exist; positions are one-based"
+        );
+    }
+
+    #[test]
+    fn test_lenient_parsing() {
+        let input =
+            "1-3 a: abcde\n1-3 b cdefg\nx-9 c: ccccccccc\n1- d: dd\n2-9 : ccccccccc\n1-3 a: aaaa\n";
+        let error = PasswordDatabase::parse(input, ParseMode::Strict)
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 12: missing \": \" between policy and password"
+        );
+
+        let database = PasswordDatabase::parse(input, ParseMode::Lenient).unwrap();
+        let lines = database
+            .entries()
+            .iter()
+            .map(|(line, _)| *line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![1, 6]);
+        let errors = database
+            .errors()
+            .iter()
+            .map(|error| (error.line, error.column))
+            .collect::<Vec<_>>();
+        assert_eq!(errors, vec![(2, 12), (3, 1), (4, 3), (5, 5)]);
+
+        let violations = Day2::violations(&database, Part::One, TextOptions::default()).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, 6);
+    }
+
+    #[test]
+    fn test_unicode_positions() {
+        // "e" followed by a combining acute accent is two code points, but one
+        // grapheme
+        let validator = PasswordValidator::new("2-3 \u{e9}: xe\u{301}\u{e9}").unwrap();
+        let graphemes = TextOptions {
+            positions:     PositionUnit::Grapheme,
+            normalization: Normalization::None,
+        };
+        let nfc_graphemes = TextOptions {
+            positions:     PositionUnit::Grapheme,
+            normalization: Normalization::Nfc,
+        };
+        assert!(!validator
+            .is_valid(Part::Two, TextOptions::default())
+            .unwrap());
+        assert!(validator.is_valid(Part::Two, graphemes).unwrap());
+        assert!(!validator.is_valid(Part::Two, nfc_graphemes).unwrap());
+        assert!(!validator.is_valid(Part::One, graphemes).unwrap());
+        assert!(validator.is_valid(Part::One, nfc_graphemes).unwrap());
+
+        // outside grapheme mode only the policy's first code point counts, as
+        // in the puzzle
+        let validator = PasswordValidator::new("2-3 e\u{301}: e\u{301}e").unwrap();
+        assert!(validator
+            .is_valid(Part::One, TextOptions::default())
+            .unwrap());
+        assert!(!validator.is_valid(Part::One, graphemes).unwrap());
+
+        // NFKC expands U+FB01 (the "fi" ligature) into two code points
+        let validator = PasswordValidator::new("2-2 \u{fb01}: fif").unwrap();
+        let nfkc_code_points = TextOptions {
+            positions:     PositionUnit::CodePoint,
+            normalization: Normalization::Nfkc,
+        };
+        assert!(validator.is_valid(Part::One, nfkc_code_points).unwrap());
+
+        // a skin-tone modified emoji, and a full-width digit that NFKC folds
+        let validator = PasswordValidator::new("1-2 \u{1f44d}: \u{1f44d}\u{1f3fd}x").unwrap();
+        assert!(validator
+            .is_valid(Part::Two, TextOptions::default())
+            .unwrap());
+        assert!(!validator.is_valid(Part::Two, graphemes).unwrap());
+
+        let validator = PasswordValidator::new("1-1 1: \u{ff11}23").unwrap();
+        let nfkc = TextOptions {
+            positions:     PositionUnit::CodePoint,
+            normalization: Normalization::Nfkc,
+        };
+        assert!(!validator
+            .is_valid(Part::One, TextOptions::default())
+            .unwrap());
+        assert!(validator.is_valid(Part::One, nfkc).unwrap());
     }
 }
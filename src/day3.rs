@@ -1,56 +1,61 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
 
 use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
 
-use crate::problem::{Part, Solved};
+use crate::grid::Grid;
+use crate::problem::{checked_product, Answer, Solved};
 
-const INPUT_FILE_PATH: &str = "src/day3/puzzle_inputs";
+const INPUT_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day3/puzzle_inputs");
 
+/// Day 3: Toboggan Trajectory
 pub struct Day3;
 
-impl Day3 {
-    fn solve(problem_part: Part, input_file_path: &str) -> Result<usize> {
-        match problem_part {
-            Part::One => {
-                let analyzer = PathAnalyzer::new(input_file_path)?;
-                analyzer.calculate_num_trees(Slope { tiles_right: 3, tiles_down: 1 })
-            },
-            Part::Two => {
-                let analyzer = PathAnalyzer::new(input_file_path)?;
-                let slopes = [
-                    Slope { tiles_right: 1, tiles_down: 1 },
-                    Slope { tiles_right: 3, tiles_down: 1 },
-                    Slope { tiles_right: 5, tiles_down: 1 },
-                    Slope { tiles_right: 7, tiles_down: 1 },
-                    Slope { tiles_right: 1, tiles_down: 2 },
-                ];
-
-                let mut tree_counts = Vec::new();
-                for slope in slopes {
-                    tree_counts.push(analyzer.calculate_num_trees(slope)?)
-                }
-                Ok(tree_counts.iter().fold(1, |acc, count| acc * count))
-            },
-        }
+impl Solved for Day3 {
+    type Input<'a> = PathAnalyzer;
+
+    const DAY: u8 = 3;
+    const INPUT_FILE_PATH: &'static str = INPUT_FILE_PATH;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        PathAnalyzer::new(input)
     }
-}
 
-impl Solved for Day3 {
-    fn print_solution(part: Part) {
-        let solution = Self::solve(part, INPUT_FILE_PATH).unwrap();
-        println!("Day 3 {} solution: {}", part, solution);
+    fn part_one(analyzer: &Self::Input<'_>) -> Result<Answer> {
+        let num_trees = analyzer.calculate_num_trees(Slope { tiles_right: 3, tiles_down: 1 })?;
+        Ok(Answer::from(num_trees))
+    }
+
+    fn part_two(analyzer: &Self::Input<'_>) -> Result<Answer> {
+        let product = analyzer.product_of_trees(&PUZZLE_SLOPES)?;
+        Ok(Answer::from(product))
     }
 }
 
+/// The slopes checked by part two.
+pub const PUZZLE_SLOPES: [Slope; 5] = [
+    Slope { tiles_right: 1, tiles_down: 1 },
+    Slope { tiles_right: 3, tiles_down: 1 },
+    Slope { tiles_right: 5, tiles_down: 1 },
+    Slope { tiles_right: 7, tiles_down: 1 },
+    Slope { tiles_right: 1, tiles_down: 2 },
+];
+
+/// A single square of the map.
 #[derive(Debug, PartialEq)]
-enum Tile {
+pub enum Tile {
     Empty,
     Tree,
 }
 
-impl Tile {
-    fn from_char(char: char) -> Result<Self> {
+/// Parses `#` as a tree and `.` as open ground.
+impl TryFrom<char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(char: char) -> Result<Self> {
         match char {
             '#' => Ok(Self::Tree),
             '.' => Ok(Self::Empty),
@@ -59,85 +64,629 @@ impl Tile {
     }
 }
 
-struct Slope {
-    tiles_down:  usize,
-    tiles_right: usize,
+impl Tile {
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Tree => '#',
+            Self::Empty => '.',
+        }
+    }
 }
 
-struct PathAnalyzer {
-    grid: Vec<Vec<Tile>>,
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
 }
 
-impl PathAnalyzer {
-    fn new(input_file_path: &str) -> Result<Self> {
-        let file = File::open(input_file_path).context("Unable to open input file")?;
-        let reader = BufReader::new(file);
-        let mut grid = Vec::new();
-
-        for line in reader.lines() {
-            let row_input = line?;
-            let len = row_input.len();
-            let mut row = Vec::with_capacity(len);
-
-            for char in row_input.chars() {
-                let tile = Tile::from_char(char)?;
-                row.push(tile);
-            }
-            grid.push(row);
+/// Which chars mark trees and which mark open ground, for maps drawn with
+/// something other than `#` and `.`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TileAlphabet {
+    pub trees: String,
+    pub open:  String,
+}
+
+impl Default for TileAlphabet {
+    fn default() -> Self {
+        Self {
+            trees: String::from("#"),
+            open:  String::from("."),
+        }
+    }
+}
+
+impl TileAlphabet {
+    fn validate(&self) -> Result<()> {
+        if self.trees.is_empty() || self.open.is_empty() {
+            return Err(anyhow!(
+                "Tile alphabet needs at least one tree and one open char"
+            ));
         }
+        if let Some(char) = self.trees.chars().find(|&char| self.open.contains(char)) {
+            return Err(anyhow!(
+                "Tile alphabet uses {:?} for both trees and open ground",
+                char
+            ));
+        }
+        Ok(())
+    }
 
+    pub fn tile(&self, char: char) -> Result<Tile> {
+        if self.trees.contains(char) {
+            Ok(Tile::Tree)
+        } else if self.open.contains(char) {
+            Ok(Tile::Empty)
+        } else {
+            Err(anyhow!("Unable to parse Tile from char {}", char))
+        }
+    }
+}
+
+/// Settings for analyzing maps beyond the puzzle's own, loaded from a JSON file
+/// like `{"slopes": [{"tiles_right": 3, "tiles_down": 1}], "trees": "^",
+/// "open": " "}`. Any key left out keeps the puzzle's value.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MapConfig {
+    pub slopes:   Vec<Slope>,
+    #[serde(flatten)]
+    pub alphabet: TileAlphabet,
+}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        Self {
+            slopes:   PUZZLE_SLOPES.to_vec(),
+            alphabet: TileAlphabet::default(),
+        }
+    }
+}
+
+impl MapConfig {
+    pub fn load(path: &str) -> Result<Self> {
+        let contents =
+            fs::read_to_string(path).context(anyhow!("Failed to read map config {}", path))?;
+        serde_json::from_str(&contents).context(anyhow!("Failed to parse map config {}", path))
+    }
+}
+
+/// How far a toboggan moves per step.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Slope {
+    pub tiles_down:  usize,
+    pub tiles_right: usize,
+}
+
+impl fmt::Display for Slope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "right {}, down {}", self.tiles_right, self.tiles_down)
+    }
+}
+
+impl FromStr for Slope {
+    type Err = anyhow::Error;
+
+    // expects a string like "3,1", for 3 tiles right and 1 down
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (right_input, down_input) = s
+            .split_once(',')
+            .context(anyhow!("Slope {} must look like <right>,<down>", s))?;
+        Ok(Self {
+            tiles_right: right_input
+                .trim()
+                .parse()
+                .context(anyhow!("Could not parse tiles right from {}", right_input))?,
+            tiles_down:  down_input
+                .trim()
+                .parse()
+                .context(anyhow!("Could not parse tiles down from {}", down_input))?,
+        })
+    }
+}
+
+/// How many trees one slope hits.
+#[derive(Debug, Serialize)]
+pub struct SlopeCount {
+    pub slope:     Slope,
+    pub num_trees: usize,
+}
+
+/// Every slope within some bounds, ranked from fewest trees hit to most.
+#[derive(Debug, Serialize)]
+pub struct SlopeSearch {
+    pub ranked: Vec<SlopeCount>,
+}
+
+impl SlopeSearch {
+    /// The slope hitting the fewest trees; ties go to the smaller step down,
+    /// then the smaller step right.
+    pub fn fewest(&self) -> Option<&SlopeCount> {
+        self.ranked.first()
+    }
+
+    /// The slope hitting the most trees, with ties broken like `fewest`.
+    pub fn most(&self) -> Option<&SlopeCount> {
+        let max_trees = self.ranked.last()?.num_trees;
+        self.ranked
+            .iter()
+            .find(|count| count.num_trees == max_trees)
+    }
+
+    /// How many slopes hit each number of trees.
+    pub fn distribution(&self) -> BTreeMap<usize, usize> {
+        let mut distribution = BTreeMap::new();
+        for count in &self.ranked {
+            *distribution.entry(count.num_trees).or_insert(0) += 1;
+        }
+        distribution
+    }
+}
+
+/// A map that repeats infinitely to the right.
+pub struct PathAnalyzer {
+    grid: Grid<Tile>,
+}
+
+impl PathAnalyzer {
+    /// Parses a map with one row of tiles per line. Every line must have the
+    /// same, non-zero number of tiles.
+    pub fn new(input: &str) -> Result<Self> {
+        Self::with_alphabet(input, &TileAlphabet::default())
+    }
+
+    /// Like `new`, but reads tiles using a custom alphabet.
+    pub fn with_alphabet(input: &str, alphabet: &TileAlphabet) -> Result<Self> {
+        alphabet.validate()?;
+        if input.lines().next().is_none() {
+            return Err(anyhow!("Map is empty"));
+        }
+        if let Some(index) = input.lines().position(str::is_empty) {
+            return Err(anyhow!("Line {} of the map is empty", index + 1));
+        }
+        let grid = Grid::parse_with(input, |char| alphabet.tile(char))?;
         Ok(Self { grid })
     }
 
-    fn calculate_num_trees(&self, slope: Slope) -> Result<usize> {
+    /// Counts the trees hit descending from the top-left corner at `slope`.
+    pub fn calculate_num_trees(&self, slope: Slope) -> Result<usize> {
+        if slope.tiles_down == 0 {
+            return Err(anyhow!("Slope must move down at least one tile per step"));
+        }
+        let width = self.grid.width();
+        // keep x within the first copy of the map, so it can't overflow
+        let step_right = slope.tiles_right % width;
+
         let mut num_trees = 0;
         // ignore the starting tile, so DON'T start at 0, 0
-        let mut x = slope.tiles_right;
+        let mut x = step_right;
         let mut y = slope.tiles_down;
 
-        while y < self.grid.len() {
+        while y < self.grid.height() {
             let next_tile = self.get_tile_at_coords(x, y)?;
             if *next_tile == Tile::Tree {
                 num_trees += 1;
             }
-            y += slope.tiles_down;
-            x += slope.tiles_right;
+            y = match y.checked_add(slope.tiles_down) {
+                Some(next_y) => next_y,
+                None => break,
+            };
+            x = (x + step_right) % width;
         }
 
         Ok(num_trees)
     }
 
+    /// Counts the trees hit descending `num_rows` rows of the map, treating it
+    /// as repeating downward as well as to the right. After some number of
+    /// steps the toboggan is back at the same spot in the map, so this only
+    /// walks one such cycle, however many rows there are.
+    pub fn calculate_num_trees_repeating(&self, slope: Slope, num_rows: u64) -> Result<u64> {
+        if slope.tiles_down == 0 {
+            return Err(anyhow!("Slope must move down at least one tile per step"));
+        }
+        let width = self.grid.width();
+        let height = self.grid.height();
+        let step_right = slope.tiles_right % width;
+        let step_down = slope.tiles_down % height;
+        // x repeats every `width / gcd` steps, and likewise for y
+        let period = lcm(
+            width / gcd(step_right, width),
+            height / gcd(step_down, height),
+        );
+
+        // ignore the starting tile, so take one step per `tiles_down` rows
+        // after the first
+        let num_steps = num_rows.saturating_sub(1) / slope.tiles_down as u64;
+        let num_periods = num_steps / period as u64;
+        let remaining_steps = (num_steps % period as u64) as usize;
+
+        let mut trees_per_period = 0;
+        let mut trees_in_remainder = 0;
+        let mut x = 0;
+        let mut y = 0;
+        for step in 1..=period {
+            x = (x + step_right) % width;
+            y = (y + step_down) % height;
+            if *self.get_tile_at_coords(x, y)? == Tile::Tree {
+                trees_per_period += 1;
+                if step <= remaining_steps {
+                    trees_in_remainder += 1;
+                }
+            }
+        }
+        Ok(num_periods * trees_per_period + trees_in_remainder)
+    }
+
+    /// Multiplies together the trees hit at each slope.
+    pub fn product_of_trees(&self, slopes: &[Slope]) -> Result<u64> {
+        let mut tree_counts = Vec::new();
+        for &slope in slopes {
+            tree_counts.push(self.calculate_num_trees(slope)?)
+        }
+        let product = checked_product(tree_counts.iter().map(|&count| count as u64))?;
+        Ok(product)
+    }
+
+    /// Tries every slope moving `0..=max_right` tiles right and `1..=max_down`
+    /// tiles down per step.
+    pub fn search_slopes(&self, max_right: usize, max_down: usize) -> Result<SlopeSearch> {
+        let mut ranked = Vec::new();
+        for tiles_down in 1..=max_down {
+            for tiles_right in 0..=max_right {
+                let slope = Slope { tiles_down, tiles_right };
+                let num_trees = self.calculate_num_trees(slope)?;
+                ranked.push(SlopeCount { slope, num_trees });
+            }
+        }
+        // stable, so equal counts stay ordered by step down, then step right
+        ranked.sort_by_key(|count| count.num_trees);
+        Ok(SlopeSearch { ranked })
+    }
+
+    /// The coordinates visited descending at `slope`, not counting the starting
+    /// tile. `x` keeps growing past the map's width rather than wrapping.
+    pub fn path(&self, slope: Slope) -> Result<Vec<(usize, usize)>> {
+        if slope.tiles_down == 0 {
+            return Err(anyhow!("Slope must move down at least one tile per step"));
+        }
+        let mut path = Vec::new();
+        for y in (slope.tiles_down..self.grid.height()).step_by(slope.tiles_down) {
+            let x = (y / slope.tiles_down)
+                .checked_mul(slope.tiles_right)
+                .context(anyhow!("Path at {} moves too far right to track", slope))?;
+            path.push((x, y));
+        }
+        Ok(path)
+    }
+
+    // callers keep `y` within the map, so only `x` actually wraps
     fn get_tile_at_coords(&self, x: usize, y: usize) -> Result<&Tile> {
-        let normalized_x = self.normalize_x(x);
         self.grid
-            .get(y)
-            .context("Could not find row in grid")?
-            .get(normalized_x)
-            .context("Could not find index in row")
+            .get_wrapping(x, y)
+            .context(anyhow!("Could not find tile at {}, {}", x, y))
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+/// Draws the map with the tiles visited by one or more slopes marked.
+pub mod render {
+    use std::collections::HashSet;
+    use std::fmt::Write;
+
+    use anyhow::{anyhow, Result};
+
+    use super::{PathAnalyzer, Slope, Tile};
+
+    const CELL_SIZE: usize = 10;
+    // the most map cells either renderer will draw; steep slopes would
+    // otherwise repeat the map far enough to exhaust memory
+    pub const MAX_RENDERED_CELLS: usize = 1_000_000;
+    // distinct colors for up to this many slopes, after which they repeat
+    const PATH_COLORS: [&str; 8] = [
+        "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#bfef45",
+    ];
+
+    /// Renders the map like the puzzle text: repeated to the right far enough
+    /// to hold the whole path, with visited open squares marked `O` and
+    /// visited trees marked `X`. Fails if that would take more than
+    /// [`MAX_RENDERED_CELLS`] cells.
+    pub fn ascii(analyzer: &PathAnalyzer, slope: Slope) -> Result<String> {
+        let path = analyzer.path(slope)?;
+        let visited = path.iter().copied().collect::<HashSet<_>>();
+        let width = rendered_width(analyzer, &path)?;
+
+        let mut output = String::new();
+        for y in 0..analyzer.grid.height() {
+            for x in 0..width {
+                let tile = analyzer.get_tile_at_coords(x, y)?;
+                let char = match (visited.contains(&(x, y)), tile) {
+                    (true, Tile::Empty) => 'O',
+                    (true, Tile::Tree) => 'X',
+                    (false, tile) => tile.as_char(),
+                };
+                output.push(char);
+            }
+            output.push('\n');
+        }
+        Ok(output)
+    }
+
+    /// Renders the map as an SVG image, with each slope's path drawn in its
+    /// own color. Fails if that would take more than [`MAX_RENDERED_CELLS`]
+    /// cells.
+    pub fn svg(analyzer: &PathAnalyzer, slopes: &[Slope]) -> Result<String> {
+        let paths = slopes
+            .iter()
+            .map(|&slope| analyzer.path(slope))
+            .collect::<Result<Vec<_>>>()?;
+        let all_visited = paths.iter().flatten().copied().collect::<Vec<_>>();
+        let width = rendered_width(analyzer, &all_visited)?;
+        let height = analyzer.grid.height();
+
+        let mut output = String::new();
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}">"#,
+            width * CELL_SIZE,
+            height * CELL_SIZE
+        )?;
+        writeln!(output, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+        for y in 0..height {
+            for x in 0..width {
+                if *analyzer.get_tile_at_coords(x, y)? == Tile::Tree {
+                    writeln!(
+                        output,
+                        r#"<rect x="{}" y="{}" width="{size}" height="{size}" fill="darkgreen"/>"#,
+                        x * CELL_SIZE,
+                        y * CELL_SIZE,
+                        size = CELL_SIZE
+                    )?;
+                }
+            }
+        }
+
+        let center = |coord: usize| coord * CELL_SIZE + CELL_SIZE / 2;
+        for (index, (slope, path)) in slopes.iter().zip(&paths).enumerate() {
+            let color = PATH_COLORS[index % PATH_COLORS.len()];
+            writeln!(
+                output,
+                r#"<g fill="{color}" stroke="{color}">"#,
+                color = color
+            )?;
+            writeln!(output, "<title>{}</title>", slope)?;
+            let mut points = format!("{},{}", center(0), center(0));
+            for &(x, y) in path {
+                write!(points, " {},{}", center(x), center(y))?;
+            }
+            writeln!(output, r#"<polyline points="{}" fill="none"/>"#, points)?;
+            for &(x, y) in path {
+                writeln!(
+                    output,
+                    r#"<circle cx="{}" cy="{}" r="{}"/>"#,
+                    center(x),
+                    center(y),
+                    CELL_SIZE / 3
+                )?;
+            }
+            writeln!(output, "</g>")?;
+        }
+        writeln!(output, "</svg>")?;
+        Ok(output)
     }
 
-    fn normalize_x(&self, x: usize) -> usize {
-        // assumes that all rows of the grid have the same len
-        let row_len = self.grid[0].len();
-        x % row_len
+    // enough copies of the map to hold every visited x, within the cell limit
+    fn rendered_width(analyzer: &PathAnalyzer, visited: &[(usize, usize)]) -> Result<usize> {
+        let map_width = analyzer.grid.width();
+        let height = analyzer.grid.height();
+        let max_x = visited.iter().map(|&(x, _)| x).max().unwrap_or(0);
+        (max_x / map_width + 1)
+            .checked_mul(map_width)
+            .filter(|width| {
+                width
+                    .checked_mul(height)
+                    .is_some_and(|cells| cells <= MAX_RENDERED_CELLS)
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "Rendering a path that reaches x = {} would take more than {} cells",
+                    max_x,
+                    MAX_RENDERED_CELLS
+                )
+            })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
+    use crate::problem::Part;
 
     const TEST_FILE_PATH: &str = "src/day3/sample";
 
     #[test]
     fn test_part_one() {
-        let solution = Day3::solve(Part::One, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 7);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day3::solution(Part::One, &input).unwrap();
+        assert_eq!(solution, Answer::Int(7));
     }
 
     #[test]
     fn test_part_two() {
-        let solution = Day3::solve(Part::Two, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 336);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day3::solution(Part::Two, &input).unwrap();
+        assert_eq!(solution, Answer::Int(336));
+    }
+
+    #[test]
+    fn test_invalid_maps() {
+        let err = PathAnalyzer::new("").err().unwrap();
+        assert_eq!(err.to_string(), "Map is empty");
+
+        let err = PathAnalyzer::new("..#\n\n#..\n").err().unwrap();
+        assert_eq!(err.to_string(), "Line 2 of the map is empty");
+
+        let err = PathAnalyzer::new("..#\n#..\n.#\n").err().unwrap();
+        assert_eq!(err.to_string(), "Line 3 has 2 cells, but line 1 has 3");
+
+        let err = PathAnalyzer::new("..#\n#x.\n").err().unwrap();
+        assert_eq!(
+            format!("{:#}", err),
+            "Failed to parse line 2: Unable to parse Tile from char x"
+        );
+    }
+
+    #[test]
+    fn test_extreme_slopes() {
+        let analyzer = PathAnalyzer::new("..\n.#\n#.\n").unwrap();
+        let stalled = analyzer.calculate_num_trees(Slope { tiles_right: 1, tiles_down: 0 });
+        assert!(stalled.is_err());
+
+        let huge_right = Slope { tiles_right: usize::MAX, tiles_down: 1 };
+        assert_eq!(analyzer.calculate_num_trees(huge_right).unwrap(), 2);
+        let huge_down = Slope { tiles_right: 1, tiles_down: usize::MAX };
+        assert_eq!(analyzer.calculate_num_trees(huge_down).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_slope_search() {
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let analyzer = PathAnalyzer::new(&input).unwrap();
+        let search = analyzer.search_slopes(7, 2).unwrap();
+        assert_eq!(search.ranked.len(), 16);
+
+        let most = search.most().unwrap();
+        assert_eq!(most.slope, Slope { tiles_down: 1, tiles_right: 3 });
+        assert_eq!(most.num_trees, 7);
+        let fewest = search.fewest().unwrap();
+        assert_eq!(fewest.slope, Slope { tiles_down: 2, tiles_right: 5 });
+        assert_eq!(fewest.num_trees, 0);
+
+        let distribution = search.distribution().into_iter().collect::<Vec<_>>();
+        assert_eq!(distribution, vec![
+            (0, 1),
+            (1, 5),
+            (2, 4),
+            (3, 3),
+            (4, 2),
+            (7, 1)
+        ]);
+
+        // the puzzle's slopes show up with the same counts as part two
+        let count_for = |tiles_right, tiles_down| {
+            let slope = Slope { tiles_down, tiles_right };
+            let count = search.ranked.iter().find(|count| count.slope == slope);
+            count.unwrap().num_trees
+        };
+        assert_eq!(count_for(1, 1), 2);
+        assert_eq!(count_for(3, 1), 7);
+        assert_eq!(count_for(5, 1), 3);
+        assert_eq!(count_for(1, 2), 2);
+    }
+
+    #[test]
+    fn test_render() {
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let analyzer = PathAnalyzer::new(&input).unwrap();
+        let slope = Slope { tiles_right: 3, tiles_down: 1 };
+
+        let ascii = render::ascii(&analyzer, slope).unwrap();
+        let rows = ascii.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 11);
+        assert_eq!(rows[0], "..##.........##.........##.......");
+        assert_eq!(rows[1], "#..O#...#..#...#...#..#...#...#..");
+        assert_eq!(rows[2], ".#....X..#..#....#..#..#....#..#.");
+        assert_eq!(rows[10], ".#..#...#.#.#..#...#.#.#..#...X.#");
+        assert_eq!(ascii.matches('X').count(), 7);
+
+        let slopes = [slope, Slope { tiles_right: 1, tiles_down: 2 }];
+        let svg = render::svg(&analyzer, &slopes).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 10 + 5);
+
+        // too wide to draw, even though the path itself is short
+        let analyzer = PathAnalyzer::new("..#\n#..\n").unwrap();
+        let steep = Slope {
+            tiles_right: 100_000_000_000,
+            tiles_down:  1,
+        };
+        assert!(render::ascii(&analyzer, steep).is_err());
+        assert!(render::svg(&analyzer, &[steep]).is_err());
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let custom_input = input.replace('#', "^").replace('.', " ");
+        let config: MapConfig = serde_json::from_str(
+            r#"{"slopes": [{"tiles_right": 3, "tiles_down": 1}], "trees": "^T", "open": " "}"#,
+        )
+        .unwrap();
+        let analyzer = PathAnalyzer::with_alphabet(&custom_input, &config.alphabet).unwrap();
+        assert_eq!(analyzer.product_of_trees(&config.slopes).unwrap(), 7);
+
+        let config: MapConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.slopes, PUZZLE_SLOPES.to_vec());
+        let analyzer = PathAnalyzer::with_alphabet(&input, &config.alphabet).unwrap();
+        assert_eq!(analyzer.product_of_trees(&config.slopes).unwrap(), 336);
+
+        assert!(PathAnalyzer::new(&custom_input).is_err());
+        let overlapping = TileAlphabet {
+            trees: String::from("#x"),
+            open:  String::from(".x"),
+        };
+        assert!(PathAnalyzer::with_alphabet(&input, &overlapping).is_err());
+    }
+
+    #[test]
+    fn test_repeating_map() {
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let analyzer = PathAnalyzer::new(&input).unwrap();
+        let height = analyzer.grid.height();
+
+        // walks every step down a map repeated to `num_rows` rows
+        let count_step_by_step = |slope: Slope, num_rows: usize| {
+            (1..)
+                .map(|step| (step * slope.tiles_right, step * slope.tiles_down))
+                .take_while(|&(_, y)| y < num_rows)
+                .filter(|&(x, y)| analyzer.grid.get_wrapping(x, y) == Some(&Tile::Tree))
+                .count() as u64
+        };
+
+        let slopes = [
+            Slope { tiles_right: 3, tiles_down: 1 },
+            Slope { tiles_right: 1, tiles_down: 2 },
+            Slope { tiles_right: 11, tiles_down: 3 },
+            Slope { tiles_right: 0, tiles_down: 1 },
+            Slope { tiles_right: 4, tiles_down: 22 },
+        ];
+        for slope in slopes {
+            let num_trees = analyzer.calculate_num_trees(slope).unwrap() as u64;
+            let repeating = analyzer.calculate_num_trees_repeating(slope, height as u64);
+            assert_eq!(repeating.unwrap(), num_trees);
+            for num_rows in [0, 1, 50, 5000] {
+                let repeating = analyzer.calculate_num_trees_repeating(slope, num_rows as u64);
+                assert_eq!(repeating.unwrap(), count_step_by_step(slope, num_rows));
+            }
+        }
+
+        let slope = Slope { tiles_right: 3, tiles_down: 1 };
+        let num_trees = analyzer.calculate_num_trees_repeating(slope, 10u64.pow(12));
+        assert_eq!(num_trees.unwrap(), 636363636363);
     }
 }
@@ -1,541 +1,967 @@
-use std::fs::read_to_string;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
+use serde::Serialize;
 
-use crate::problem::{Part, Solved};
+use crate::problem::{Answer, Part, Solved};
 
-// TODO(dkimbel): POTENTIAL IMPROVEMENTS
-//   - In part_two ValidCredential::new, don't re-do the work of creating
-//     CoreCredentials: right now I'm potentially doing it twice, once for
-//     Passport and once for NorthPoleID. Only build it once, and either
-//     copy/clone it or just pass a reference to pass/north.
-
-const INPUT_FILE_PATH: &str = "src/day4/puzzle_inputs";
+const INPUT_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day4/puzzle_inputs");
 
+/// Day 4: Passport Processing
 pub struct Day4;
 
-impl Day4 {
-    fn solve(problem_part: Part, input_file_path: &str) -> Result<usize> {
-        let inputs = read_to_string(input_file_path).context("Failed to read input file")?;
-        let split_inputs = inputs.split("\n\n");
+impl Solved for Day4 {
+    type Input<'a> = Batch<'a>;
 
-        match problem_part {
-            Part::One => {
-                let valid_credentials = split_inputs
-                    .map(part_one::ValidCredential::new)
-                    .filter_map(|maybe_cred| maybe_cred.ok());
+    const DAY: u8 = 4;
+    const INPUT_FILE_PATH: &'static str = INPUT_FILE_PATH;
 
-                Ok(valid_credentials.count())
-            },
-            Part::Two => {
-                let valid_credentials = split_inputs
-                    .map(part_two::ValidCredential::new)
-                    .filter_map(|maybe_cred| maybe_cred.ok());
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(Batch {
+            records:       Record::parse_batch(input),
+            puzzle_schema: schema::Schema::puzzle(Part::Two)?,
+        })
+    }
 
-                Ok(valid_credentials.count())
-            },
-        }
+    fn part_one(batch: &Self::Input<'_>) -> Result<Answer> {
+        let schema = batch.puzzle_schema.without_rules();
+        Ok(Answer::from(Self::count_valid(&batch.records, &schema)))
+    }
+
+    fn part_two(batch: &Self::Input<'_>) -> Result<Answer> {
+        let num_valid = Self::count_valid(&batch.records, &batch.puzzle_schema);
+        Ok(Answer::from(num_valid))
     }
 }
 
-mod part_two {
-    use std::str::FromStr;
+/// A passport batch, tokenized once for both parts.
+pub struct Batch<'a> {
+    pub records:   Vec<Record<'a>>,
+    // the puzzle's Part Two schema; Part One checks the same fields without
+    // their rules
+    puzzle_schema: schema::Schema,
+}
 
-    use anyhow::{anyhow, Context, Result};
-    use regex::Regex;
+/// One record of a batch, which may span several lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record<'a> {
+    /// One-based line the record starts on.
+    pub line:   usize,
+    pub tokens: Vec<Token<'a>>,
+}
 
-    pub enum ValidCredential<'a> {
-        Pass(Passport<'a>),
-        North(NorthPoleID),
-    }
+/// One whitespace-separated `key:value` token of a record.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// The whole token when it has no `:`.
+    pub key:    &'a str,
+    /// `None` when the token has no `:`.
+    pub value:  Option<&'a str>,
+    /// One-based line and column the token starts at.
+    pub line:   usize,
+    pub column: usize,
+}
 
-    impl<'a> ValidCredential<'a> {
-        pub fn new(input: &'a str) -> Result<Self> {
-            let passport = Passport::new(input).map(Self::Pass);
-            if passport.is_ok() {
-                passport
-            } else {
-                NorthPoleID::new(input).map(Self::North)
+impl<'a> Record<'a> {
+    /// Splits a batch into records separated by blank lines. Extra blank
+    /// lines, including trailing ones, don't make empty records.
+    pub fn parse_batch(input: &'a str) -> Vec<Self> {
+        let mut records = Vec::new();
+        let mut record = Record { line: 1, tokens: Vec::new() };
+        for (index, text) in input.lines().enumerate() {
+            let line = index + 1;
+            if text.is_empty() {
+                if !record.tokens.is_empty() {
+                    let tokens = mem::take(&mut record.tokens);
+                    records.push(Record { line: record.line, tokens });
+                }
+                continue;
             }
+            if record.tokens.is_empty() {
+                record.line = line;
+            }
+            record.tokens.extend(Token::parse_line(text, line));
+        }
+        if !record.tokens.is_empty() {
+            records.push(record);
         }
+        records
     }
 
-    enum EyeColor {
-        Amber,
-        Blue,
-        Brown,
-        Gray,
-        Green,
-        Hazel,
-        Other,
+    /// The value of the first token with the given key.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.tokens
+            .iter()
+            .find(|token| token.key == key)
+            .and_then(|token| token.value)
     }
+}
 
-    impl FromStr for EyeColor {
-        type Err = anyhow::Error;
-
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            match s {
-                "amb" => Ok(Self::Amber),
-                "blu" => Ok(Self::Blue),
-                "brn" => Ok(Self::Brown),
-                "gry" => Ok(Self::Gray),
-                "grn" => Ok(Self::Green),
-                "hzl" => Ok(Self::Hazel),
-                "oth" => Ok(Self::Other),
-                _ => Err(anyhow!("Invalid eye color {}", s)),
+impl<'a> Token<'a> {
+    fn parse_line(text: &'a str, line: usize) -> impl Iterator<Item = Self> + 'a {
+        let mut column = 1;
+        text.split(char::is_whitespace).filter_map(move |token| {
+            let start = column;
+            column += token.chars().count() + 1;
+            if token.is_empty() {
+                return None;
             }
-        }
+            let (key, value) = match token.split_once(':') {
+                Some((key, value)) => (key, Some(value)),
+                None => (token, None),
+            };
+            Some(Self { key, value, line, column: start })
+        })
     }
+}
 
-    #[derive(Debug)]
-    enum Measure {
-        Inches,
-        Centimeters,
+impl Day4 {
+    pub fn count_valid(records: &[Record<'_>], schema: &schema::Schema) -> usize {
+        records
+            .iter()
+            .filter(|record| schema.is_valid(record))
+            .count()
     }
 
-    impl FromStr for Measure {
-        type Err = anyhow::Error;
+    /// Checks every record against the schema, reporting every problem with
+    /// each one rather than stopping at the first.
+    pub fn explain(
+        records: &[Record<'_>],
+        schema: &schema::Schema,
+        mode: CheckMode,
+    ) -> Vec<RecordReport> {
+        records
+            .iter()
+            .enumerate()
+            .map(|(index, record)| schema.check(index + 1, record, mode))
+            .collect()
+    }
 
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            match s {
-                "in" => Ok(Self::Inches),
-                "cm" => Ok(Self::Centimeters),
-                _ => Err(anyhow!("Could not parse measure from {}", s)),
+    /// Counts how often each failure reason, like `missing hgt`, `invalid
+    /// hcl` or `duplicate byr`, shows up across the reports.
+    pub fn failure_histogram(reports: &[RecordReport]) -> BTreeMap<String, usize> {
+        let mut histogram = BTreeMap::new();
+        for report in reports {
+            let missing = report
+                .missing
+                .iter()
+                .map(|field| format!("missing {}", field));
+            let invalid = report
+                .invalid
+                .iter()
+                .map(|failure| format!("{} {}", failure.kind, failure.field));
+            for reason in missing.chain(invalid) {
+                *histogram.entry(reason).or_insert(0) += 1;
             }
         }
+        histogram
     }
 
-    struct Height(u32, Measure);
-
-    impl FromStr for Height {
-        type Err = anyhow::Error;
-
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let chars = s.chars().collect::<Vec<_>>();
-            let len = chars.len();
-            if chars.len() < 3 {
-                return Err(anyhow!("Height must have at least three chars"));
+    /// The passport ID of every record the schema accepts, as written. IDs
+    /// are held to the schema's `pid` width, or the puzzle's if it has none;
+    /// accepted records whose `pid` doesn't fit, as Part One allows, are
+    /// counted as skipped.
+    pub fn passport_ids(
+        records: &[Record<'_>],
+        schema: &schema::Schema,
+        mode: CheckMode,
+    ) -> PassportIds {
+        let num_digits = match schema.field("pid").and_then(|spec| spec.rule.as_ref()) {
+            Some(schema::Rule::Digits { count }) => *count,
+            _ => schema::PassportId::PUZZLE_DIGITS,
+        };
+        let mut ids = Vec::new();
+        let mut skipped = 0;
+        for (index, record) in records.iter().enumerate() {
+            if !schema.check(index + 1, record, mode).is_valid() {
+                continue;
             }
-
-            let measure = Measure::from_str(&chars[(len - 2)..].iter().collect::<String>())?;
-            let magnitude: &u32 = &chars[..(len - 2)]
-                .iter()
-                .collect::<String>()
-                .parse::<u32>()
-                .context("Failed to parse u32 from Height")?;
-
-            let required_range = match measure {
-                Measure::Inches => 59_u32..=76_u32,
-                Measure::Centimeters => 150_u32..=193_u32,
+            let Some(pid) = record.get("pid") else {
+                continue;
             };
-            if required_range.contains(magnitude) {
-                Ok(Height(*magnitude, measure))
-            } else {
-                Err(anyhow!(
-                    "Height magnitude {} out of range for measurement {:?}",
-                    magnitude,
-                    measure
-                ))
+            match schema::PassportId::with_digits(pid, num_digits) {
+                Ok(id) => ids.push(id),
+                Err(_) => skipped += 1,
             }
         }
+        PassportIds { ids, skipped }
     }
+}
 
-    struct HairColor(u32);
+/// The passport IDs exported from a batch.
+#[derive(Debug)]
+pub struct PassportIds {
+    pub ids:     Vec<schema::PassportId>,
+    /// Valid records whose `pid` isn't a digit string of the expected width.
+    pub skipped: usize,
+}
 
-    impl FromStr for HairColor {
-        type Err = anyhow::Error;
+/// How closely to check the keys in a passport record.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckMode {
+    /// Also flag keys that appear more than once or that the schema doesn't
+    /// list.
+    Strict,
+    /// Check every value against its rule, but ignore unknown keys and allow
+    /// repeated ones.
+    Lenient,
+}
 
-        fn from_str(s: &str) -> Result<Self, Self::Err> {
-            let chars = s.chars().collect::<Vec<_>>();
-            if chars[0] != '#' {
-                return Err(anyhow!("Hair color string must start with #"));
-            }
-            if chars.len() != 7 {
-                return Err(anyhow!("Hair color must be seven characters total"));
-            }
+/// Everything wrong with one record of a passport batch.
+#[derive(Debug, Serialize)]
+pub struct RecordReport {
+    /// One-based position of the record in the batch.
+    pub index:   usize,
+    /// One-based line the record starts on.
+    pub line:    usize,
+    pub missing: Vec<String>,
+    pub invalid: Vec<FieldFailure>,
+}
 
-            let mut color: u32 = 0;
-            for (i, char) in chars[1..].iter().enumerate() {
-                let char = char.to_digit(16).context("Char must be hex digit")?;
-                color += format!("{}", char)
-                    .parse::<u32>()
-                    .context("Digit must be u32")?
-                    .pow(u32::try_from(i).context("Must convert usize to u32")?);
-            }
-            Ok(HairColor(color))
-        }
-    }
+/// A field whose value breaks a rule, or a token that isn't a usable field.
+#[derive(Debug, Serialize)]
+pub struct FieldFailure {
+    pub kind:   FailureKind,
+    pub field:  String,
+    pub value:  String,
+    pub rule:   String,
+    /// One-based line and column the token starts at.
+    pub line:   usize,
+    pub column: usize,
+}
 
-    struct CoreCredentialsBuilder {
-        byr: Option<u32>,
-        iyr: Option<u32>,
-        eyr: Option<u32>,
-        hgt: Option<Height>,
-        hcl: Option<HairColor>,
-        ecl: Option<EyeColor>,
-        pid: Option<u32>,
-    }
-
-    // This is a ton of boilerplate, and might not actually be any more performant
-    // than just avoiding builders entirely and using `find` multiple times on the
-    // input string (even though, in that case, we'd inspect the early chars of
-    // the input string multiple times). Still, this was a nice opportunity to learn
-    // the Builder pattern.
-    impl CoreCredentialsBuilder {
-        fn build_from_input(input: &str) -> Result<CoreCredentials> {
-            let mut builder = Self::new();
-            let tag_value_pairs = input.split_whitespace();
-            for pair in tag_value_pairs {
-                let (tag, val) = pair
-                    .split_once(':')
-                    .context(anyhow!("Couldn't split tag-value pair {}", pair))?;
-                builder = match tag {
-                    "byr" => builder.byr(val)?,
-                    "iyr" => builder.iyr(val)?,
-                    "eyr" => builder.eyr(val)?,
-                    "hgt" => builder.hgt(val)?,
-                    "hcl" => builder.hcl(val)?,
-                    "ecl" => builder.ecl(val)?,
-                    "pid" => builder.pid(val)?,
-                    _ => builder,
-                }
-            }
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The value breaks the field's rule.
+    Invalid,
+    /// The token has no `:` separating its key from its value.
+    Malformed,
+    /// The key was already set earlier in the record; strict mode only.
+    Duplicate,
+    /// The schema doesn't list the key; strict mode only.
+    Unknown,
+}
 
-            builder.build()
-        }
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            Self::Invalid => "invalid",
+            Self::Malformed => "malformed",
+            Self::Duplicate => "duplicate",
+            Self::Unknown => "unknown",
+        };
+        write!(f, "{}", kind)
+    }
+}
 
-        fn new() -> Self {
-            Self {
-                byr: None,
-                iyr: None,
-                eyr: None,
-                hgt: None,
-                hcl: None,
-                ecl: None,
-                pid: None,
-            }
-        }
+impl RecordReport {
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.invalid.is_empty()
+    }
+}
 
-        fn byr(mut self, byr: &str) -> Result<Self> {
-            let byr = byr
-                .parse::<u32>()
-                .context(anyhow!("Could not parse byr from {}", byr))?;
-            if (1920u32..=2002u32).contains(&byr) {
-                self.byr = Some(byr);
-                Ok(self)
-            } else {
-                Err(anyhow!("byr out of valid range"))
-            }
+impl fmt::Display for RecordReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Record {} (line {}):", self.index, self.line)?;
+        if self.is_valid() {
+            return write!(f, " valid");
         }
-
-        fn iyr(mut self, iyr: &str) -> Result<Self> {
-            let iyr = iyr
-                .parse::<u32>()
-                .context(anyhow!("Could not parse iyr from {}", iyr))?;
-            if (2010u32..=2020u32).contains(&iyr) {
-                self.iyr = Some(iyr);
-                Ok(self)
-            } else {
-                Err(anyhow!("iyr out of valid range"))
-            }
+        if !self.missing.is_empty() {
+            write!(f, " missing {}", self.missing.join(", "))?;
         }
-
-        fn eyr(mut self, eyr: &str) -> Result<Self> {
-            let eyr = eyr
-                .parse::<u32>()
-                .context(anyhow!("Could not parse eyr from {}", eyr))?;
-            if (2020u32..=2030u32).contains(&eyr) {
-                self.eyr = Some(eyr);
-                Ok(self)
-            } else {
-                Err(anyhow!("eyr out of valid range"))
-            }
+        for failure in &self.invalid {
+            write!(
+                f,
+                "\n  line {}, column {}: {} {:?}: {}",
+                failure.line, failure.column, failure.field, failure.value, failure.rule
+            )?;
         }
+        Ok(())
+    }
+}
 
-        fn hgt(mut self, hgt: &str) -> Result<Self> {
-            let hgt = Height::from_str(hgt)?;
-            self.hgt = Some(hgt);
-            Ok(self)
-        }
+/// The rules a batch of passport records is checked against: which fields
+/// each record needs, and what their values must look like.
+pub mod schema {
+    use std::collections::BTreeMap;
+    use std::fmt;
+    use std::fs;
+    use std::str::FromStr;
 
-        fn hcl(mut self, hcl: &str) -> Result<Self> {
-            let hcl = HairColor::from_str(hcl)?;
-            self.hcl = Some(hcl);
-            Ok(self)
-        }
+    use anyhow::{anyhow, Context, Result};
+    use serde::{Deserialize, Serialize};
 
-        fn ecl(mut self, ecl: &str) -> Result<Self> {
-            let ecl = EyeColor::from_str(ecl)?;
-            self.ecl = Some(ecl);
-            Ok(self)
-        }
+    use super::{CheckMode, FailureKind, FieldFailure, Record, RecordReport, Token};
+    use crate::problem::Part;
 
-        fn pid(mut self, pid: &str) -> Result<Self> {
-            if pid.chars().count() != 9 {
-                return Err(anyhow!("pid must be nine digits"));
-            }
-            let pid = pid
-                .parse::<u32>()
-                .context(anyhow!("Could not parse pid from {}", pid))?;
-            self.pid = Some(pid);
-            Ok(self)
-        }
+    const PUZZLE_SCHEMA: &str = include_str!("day4/schema.toml");
 
-        fn build(self) -> Result<CoreCredentials> {
-            Ok(CoreCredentials {
-                byr: self.byr.context("Missing byr")?,
-                iyr: self.iyr.context("Missing iyr")?,
-                eyr: self.eyr.context("Missing eyr")?,
-                hgt: self.hgt.context("Missing hgt")?,
-                hcl: self.hcl.context("Missing hcl")?,
-                ecl: self.ecl.context("Missing ecl")?,
-                pid: self.pid.context("Missing pid")?,
-            })
-        }
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Schema {
+        pub fields: Vec<FieldSpec>,
     }
 
-    #[allow(dead_code)]
-    struct CoreCredentials {
-        byr: u32,
-        iyr: u32,
-        eyr: u32,
-        hgt: Height,
-        hcl: HairColor,
-        ecl: EyeColor,
-        pid: u32,
+    /// One field a record may carry. Optional fields are still checked
+    /// against their rule when present.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct FieldSpec {
+        pub name:     String,
+        #[serde(default = "required_by_default")]
+        pub required: bool,
+        #[serde(default)]
+        pub rule:     Option<Rule>,
     }
 
-    impl CoreCredentials {
-        fn new(input: &str) -> Result<Self> {
-            CoreCredentialsBuilder::build_from_input(input)
-        }
+    fn required_by_default() -> bool {
+        true
     }
 
-    #[allow(dead_code)]
-    pub struct Passport<'a> {
-        core_credentials: CoreCredentials,
-        cid: &'a str,
+    /// What a field's value must look like.
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    pub enum Rule {
+        /// A year between `min` and `max`, inclusive.
+        YearRange { min: u32, max: u32 },
+        /// A number followed by one of `units`, like `183cm`, within that
+        /// unit's bounds.
+        Height { units: BTreeMap<String, Bounds> },
+        /// A color like `#1a2b3c`.
+        HexColor,
+        /// Exactly one of `values`.
+        OneOf { values: Vec<String> },
+        /// Exactly `count` ASCII digits.
+        Digits { count: usize },
     }
 
-    impl<'a> Passport<'a> {
-        fn new(input: &'a str) -> Result<Self> {
-            let core_credentials = CoreCredentials::new(input)?;
-            let re = Regex::new(r"cid:(\S+)").context("Failed to build Regex pattern")?;
-            let captures = re.captures(input).context("Failed to capture anything")?;
-            let &cid = &captures.get(1).context("Failed to capture cid")?.as_str();
-
-            Ok(Self { core_credentials, cid })
-        }
+    /// An inclusive range of numbers.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    pub struct Bounds {
+        pub min: u32,
+        pub max: u32,
     }
 
-    #[allow(dead_code)]
-    pub struct NorthPoleID {
-        core_credentials: CoreCredentials,
+    impl Bounds {
+        fn contains(&self, num: u32) -> bool {
+            (self.min..=self.max).contains(&num)
+        }
     }
 
-    impl NorthPoleID {
-        fn new(input: &str) -> Result<Self> {
-            let core_credentials = CoreCredentials::new(input)?;
-            Ok(Self { core_credentials })
+    impl Rule {
+        /// Checks `value` of the field named `field`, which is only used to
+        /// word the error.
+        pub fn check(&self, field: &str, value: &str) -> Result<()> {
+            match self {
+                Self::YearRange { min, max } => {
+                    let year = value.parse::<u32>().context(anyhow!(
+                        "Could not parse {} from {}",
+                        field,
+                        value
+                    ))?;
+                    if !(*min..=*max).contains(&year) {
+                        return Err(anyhow!("{} must be between {} and {}", field, min, max));
+                    }
+                },
+                Self::Height { units } => {
+                    let unit_start = value
+                        .find(|char: char| !char.is_ascii_digit())
+                        .unwrap_or(value.len());
+                    let (magnitude, unit) = value.split_at(unit_start);
+                    let bounds = units.get(unit).context(anyhow!(
+                        "{} must end in one of {}",
+                        field,
+                        units.keys().cloned().collect::<Vec<_>>().join(", ")
+                    ))?;
+                    let magnitude = magnitude.parse::<u32>().context(anyhow!(
+                        "Could not parse {} from {}",
+                        field,
+                        value
+                    ))?;
+                    if !bounds.contains(magnitude) {
+                        return Err(anyhow!(
+                            "{} in {} must be between {} and {}",
+                            field,
+                            unit,
+                            bounds.min,
+                            bounds.max
+                        ));
+                    }
+                },
+                Self::HexColor => {
+                    HairColor::from_str(value)?;
+                },
+                Self::OneOf { values } => {
+                    if !values.iter().any(|allowed| allowed == value) {
+                        return Err(anyhow!("{} must be one of {}", field, values.join(", ")));
+                    }
+                },
+                Self::Digits { count } => {
+                    PassportId::with_digits(value, *count).context(anyhow!(
+                        "{} must be {} digits",
+                        field,
+                        count
+                    ))?;
+                },
+            }
+            Ok(())
         }
     }
-}
 
-mod part_one {
-    use anyhow::{anyhow, Context, Result};
-    use regex::Regex;
-
-    pub enum ValidCredential<'a> {
-        Pass(Passport<'a>),
-        North(NorthPoleID<'a>),
-    }
+    impl Schema {
+        /// The puzzle's own rules, from the checked-in `schema.toml`: Part One
+        /// only needs the fields present, so it drops every rule, while Part
+        /// Two checks their values too.
+        pub fn puzzle(part: Part) -> Result<Self> {
+            let schema: Self =
+                toml::from_str(PUZZLE_SCHEMA).context("Failed to parse the puzzle schema")?;
+            let schema = schema.validate("the puzzle schema")?;
+            Ok(match part {
+                Part::One => schema.without_rules(),
+                Part::Two => schema,
+            })
+        }
 
-    impl<'a> ValidCredential<'a> {
-        pub fn new(input: &'a str) -> Result<Self> {
-            let passport = Passport::new(input).map(Self::Pass);
-            if passport.is_ok() {
-                passport
+        /// The same fields, each only needing to be present.
+        pub fn without_rules(&self) -> Self {
+            let fields = self
+                .fields
+                .iter()
+                .map(|field| FieldSpec { rule: None, ..field.clone() })
+                .collect();
+            Self { fields }
+        }
+
+        /// Reads a schema from a JSON file if `path` ends in `.json`, and from
+        /// a TOML file otherwise.
+        pub fn load(path: &str) -> Result<Self> {
+            let contents =
+                fs::read_to_string(path).context(anyhow!("Failed to read schema {}", path))?;
+            let schema: Self = if path.ends_with(".json") {
+                serde_json::from_str(&contents)
+                    .context(anyhow!("Failed to parse schema {}", path))?
             } else {
-                NorthPoleID::new(input).map(Self::North)
+                toml::from_str(&contents).context(anyhow!("Failed to parse schema {}", path))?
+            };
+            schema.validate(&format!("schema {}", path))
+        }
+
+        // `source` names the schema in errors
+        fn validate(self, source: &str) -> Result<Self> {
+            for (index, field) in self.fields.iter().enumerate() {
+                if self.fields[..index]
+                    .iter()
+                    .any(|other| other.name == field.name)
+                {
+                    return Err(anyhow!(
+                        "The {} lists field {} more than once",
+                        source,
+                        field.name
+                    ));
+                }
             }
+            Ok(self)
         }
-    }
 
-    struct CoreCredentialsBuilder<'a> {
-        byr: Option<&'a str>,
-        iyr: Option<&'a str>,
-        eyr: Option<&'a str>,
-        hgt: Option<&'a str>,
-        hcl: Option<&'a str>,
-        ecl: Option<&'a str>,
-        pid: Option<&'a str>,
-    }
-
-    // This is a ton of boilerplate, and might not actually be any more performant
-    // than just avoiding builders entirely and using `find` multiple times on the
-    // input string (even though, in that case, we'd inspect the early chars of
-    // the input string multiple times). Still, this was a nice opportunity to learn
-    // the Builder pattern.
-    impl<'a> CoreCredentialsBuilder<'a> {
-        fn build_from_input(input: &'a str) -> Result<CoreCredentials<'a>> {
-            let mut builder = Self::new();
-            let tag_value_pairs = input.split_whitespace();
-            for pair in tag_value_pairs {
-                let (tag, val) = pair
-                    .split_once(':')
-                    .context(anyhow!("Couldn't split tag-value pair {}", pair))?;
-                builder = match tag {
-                    "byr" => builder.byr(val),
-                    "iyr" => builder.iyr(val),
-                    "eyr" => builder.eyr(val),
-                    "hgt" => builder.hgt(val),
-                    "hcl" => builder.hcl(val),
-                    "ecl" => builder.ecl(val),
-                    "pid" => builder.pid(val),
-                    _ => builder,
+        pub fn field(&self, name: &str) -> Option<&FieldSpec> {
+            self.fields.iter().find(|field| field.name == name)
+        }
+
+        /// Checks one record leniently, as the puzzle does.
+        pub fn is_valid(&self, record: &Record<'_>) -> bool {
+            self.check(1, record, CheckMode::Lenient).is_valid()
+        }
+
+        /// Reports every problem with one record; `index` is only used to
+        /// label the report.
+        pub fn check(&self, index: usize, record: &Record<'_>, mode: CheckMode) -> RecordReport {
+            // the first token setting each key
+            let mut present: Vec<&Token<'_>> = Vec::new();
+            let mut invalid = Vec::new();
+            for token in &record.tokens {
+                let mut fail = |kind, value: &str, rule| {
+                    invalid.push(FieldFailure {
+                        kind,
+                        field: String::from(token.key),
+                        value: String::from(value),
+                        rule,
+                        line: token.line,
+                        column: token.column,
+                    })
+                };
+                let Some(value) = token.value else {
+                    fail(
+                        FailureKind::Malformed,
+                        "",
+                        String::from("must be a key:value pair"),
+                    );
+                    continue;
+                };
+                let field = token.key;
+                let first_seen = present.iter().find(|seen| seen.key == field);
+                match (mode, first_seen) {
+                    (CheckMode::Strict, Some(first)) => fail(
+                        FailureKind::Duplicate,
+                        value,
+                        format!(
+                            "already set on line {}, column {}",
+                            first.line, first.column
+                        ),
+                    ),
+                    (_, Some(_)) => (),
+                    (_, None) => present.push(token),
+                }
+                let Some(spec) = self.field(field) else {
+                    if mode == CheckMode::Strict {
+                        fail(
+                            FailureKind::Unknown,
+                            value,
+                            String::from("not a field in the schema"),
+                        );
+                    }
+                    continue;
+                };
+                if let Some(Err(err)) = spec.rule.as_ref().map(|rule| rule.check(field, value)) {
+                    fail(FailureKind::Invalid, value, format!("{:#}", err));
                 }
             }
-
-            builder.build()
-        }
-
-        fn new() -> Self {
-            Self {
-                byr: None,
-                iyr: None,
-                eyr: None,
-                hgt: None,
-                hcl: None,
-                ecl: None,
-                pid: None,
+            let missing = self
+                .fields
+                .iter()
+                .filter(|spec| spec.required && !present.iter().any(|seen| seen.key == spec.name))
+                .map(|spec| spec.name.clone())
+                .collect();
+            RecordReport {
+                index,
+                line: record.line,
+                missing,
+                invalid,
             }
         }
+    }
 
-        fn byr(mut self, byr: &'a str) -> Self {
-            self.byr = Some(byr);
-            self
-        }
-
-        fn iyr(mut self, iyr: &'a str) -> Self {
-            self.iyr = Some(iyr);
-            self
-        }
-
-        fn eyr(mut self, eyr: &'a str) -> Self {
-            self.eyr = Some(eyr);
-            self
-        }
+    /// A 24-bit RGB color, written like `#1a2b3c`.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct HairColor(u32);
 
-        fn hgt(mut self, hgt: &'a str) -> Self {
-            self.hgt = Some(hgt);
-            self
+    impl HairColor {
+        pub fn r(&self) -> u8 {
+            (self.0 >> 16) as u8
         }
 
-        fn hcl(mut self, hcl: &'a str) -> Self {
-            self.hcl = Some(hcl);
-            self
+        pub fn g(&self) -> u8 {
+            (self.0 >> 8) as u8
         }
 
-        fn ecl(mut self, ecl: &'a str) -> Self {
-            self.ecl = Some(ecl);
-            self
+        pub fn b(&self) -> u8 {
+            self.0 as u8
         }
 
-        fn pid(mut self, pid: &'a str) -> Self {
-            self.pid = Some(pid);
-            self
-        }
-
-        fn build(self) -> Result<CoreCredentials<'a>> {
-            Ok(CoreCredentials {
-                byr: self.byr.context("Missing byr")?,
-                iyr: self.iyr.context("Missing iyr")?,
-                eyr: self.eyr.context("Missing eyr")?,
-                hgt: self.hgt.context("Missing hgt")?,
-                hcl: self.hcl.context("Missing hcl")?,
-                ecl: self.ecl.context("Missing ecl")?,
-                pid: self.pid.context("Missing pid")?,
-            })
+        pub fn to_hex_string(&self) -> String {
+            format!("#{:06x}", self.0)
         }
     }
 
-    #[allow(dead_code)]
-    struct CoreCredentials<'a> {
-        byr: &'a str,
-        iyr: &'a str,
-        eyr: &'a str,
-        hgt: &'a str,
-        hcl: &'a str,
-        ecl: &'a str,
-        pid: &'a str,
-    }
+    impl FromStr for HairColor {
+        type Err = anyhow::Error;
 
-    impl<'a> CoreCredentials<'a> {
-        fn new(input: &'a str) -> Result<Self> {
-            CoreCredentialsBuilder::build_from_input(input)
+        // expects exactly six lowercase hex digits after the #
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let digits = s
+                .strip_prefix('#')
+                .context(anyhow!("Hair color {} must start with #", s))?;
+            if let Some(char) = digits
+                .chars()
+                .find(|char| !matches!(char, '0'..='9' | 'a'..='f'))
+            {
+                return Err(anyhow!(
+                    "Hair color {} must only use lowercase hex digits, not {:?}",
+                    s,
+                    char
+                ));
+            }
+            if digits.len() != 6 {
+                return Err(anyhow!(
+                    "Hair color {} must have six hex digits, not {}",
+                    s,
+                    digits.len()
+                ));
+            }
+            let color = u32::from_str_radix(digits, 16)
+                .context(anyhow!("Could not parse hair color from {}", s))?;
+            Ok(HairColor(color))
         }
     }
 
-    #[allow(dead_code)]
-    pub struct Passport<'a> {
-        core_credentials: CoreCredentials<'a>,
-        cid: &'a str,
-    }
-
-    impl<'a> Passport<'a> {
-        fn new(input: &'a str) -> Result<Self> {
-            let core_credentials = CoreCredentials::new(input)?;
-            let re = Regex::new(r"cid:(\S+)").context("Failed to build Regex pattern")?;
-            let captures = re.captures(input).context("Failed to capture anything")?;
-            let &cid = &captures.get(1).context("Failed to capture cid")?.as_str();
+    /// A passport ID, kept exactly as written so leading zeros survive, like
+    /// `000000001`.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+    #[serde(transparent)]
+    pub struct PassportId(String);
+
+    impl PassportId {
+        /// How many digits the puzzle's passport IDs have.
+        pub const PUZZLE_DIGITS: usize = 9;
+
+        /// Accepts exactly `num_digits` ASCII digits, so signs, spaces and
+        /// other forms `u32` parsing would tolerate are rejected.
+        pub fn with_digits(s: &str, num_digits: usize) -> Result<Self> {
+            if let Some(char) = s.chars().find(|char| !char.is_ascii_digit()) {
+                return Err(anyhow!(
+                    "Passport ID {} must only use ASCII digits, not {:?}",
+                    s,
+                    char
+                ));
+            }
+            if s.len() != num_digits {
+                return Err(anyhow!(
+                    "Passport ID {} must have {} digits, not {}",
+                    s,
+                    num_digits,
+                    s.len()
+                ));
+            }
+            Ok(Self(String::from(s)))
+        }
 
-            Ok(Self { core_credentials, cid })
+        pub fn as_str(&self) -> &str {
+            &self.0
         }
     }
 
-    #[allow(dead_code)]
-    pub struct NorthPoleID<'a> {
-        core_credentials: CoreCredentials<'a>,
-    }
+    impl FromStr for PassportId {
+        type Err = anyhow::Error;
 
-    impl<'a> NorthPoleID<'a> {
-        fn new(input: &'a str) -> Result<Self> {
-            let core_credentials = CoreCredentials::new(input)?;
-            Ok(Self { core_credentials })
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Self::with_digits(s, Self::PUZZLE_DIGITS)
         }
     }
-}
 
-impl Solved for Day4 {
-    fn print_solution(part: Part) {
-        let solution = Self::solve(part, INPUT_FILE_PATH).unwrap();
-        println!("Day 4 {} solution: {}", part, solution);
+    impl fmt::Display for PassportId {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
+    use std::str::FromStr;
+
+    use super::schema::{Rule, Schema};
     use super::*;
+    use crate::problem::Part;
 
     const PART_ONE_TEST_FILE_PATH: &str = "src/day4/part_one_sample";
     const PART_TWO_VALIDS_FILE_PATH: &str = "src/day4/part_two_valids";
     const PART_TWO_INVALIDS_FILE_PATH: &str = "src/day4/part_two_invalids";
+    const SCHEMA_FILE_PATH: &str = "src/day4/schema.toml";
 
     #[test]
     fn test_part_one() {
-        let solution = Day4::solve(Part::One, PART_ONE_TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 2);
+        let input = fs::read_to_string(PART_ONE_TEST_FILE_PATH).unwrap();
+        let solution = Day4::solution(Part::One, &input).unwrap();
+        assert_eq!(solution, Answer::Int(2));
     }
 
     #[test]
     fn test_part_two_valids() {
-        let solution = Day4::solve(Part::Two, PART_TWO_VALIDS_FILE_PATH).unwrap();
-        assert_eq!(solution, 4);
+        let input = fs::read_to_string(PART_TWO_VALIDS_FILE_PATH).unwrap();
+        let solution = Day4::solution(Part::Two, &input).unwrap();
+        assert_eq!(solution, Answer::Int(4));
     }
 
     #[test]
     fn test_part_two_invalids() {
-        let solution = Day4::solve(Part::Two, PART_TWO_INVALIDS_FILE_PATH).unwrap();
-        assert_eq!(solution, 0);
+        let input = fs::read_to_string(PART_TWO_INVALIDS_FILE_PATH).unwrap();
+        let solution = Day4::solution(Part::Two, &input).unwrap();
+        assert_eq!(solution, Answer::Int(0));
+    }
+
+    #[test]
+    fn test_hair_color() {
+        let color = schema::HairColor::from_str("#1a2b3c").unwrap();
+        assert_eq!((color.r(), color.g(), color.b()), (0x1a, 0x2b, 0x3c));
+        assert_eq!(color.to_hex_string(), "#1a2b3c");
+        let color = schema::HairColor::from_str("#000000").unwrap();
+        assert_eq!(color.to_hex_string(), "#000000");
+        let color = schema::HairColor::from_str("#ffffff").unwrap();
+        assert_eq!((color.r(), color.g(), color.b()), (255, 255, 255));
+
+        for invalid in [
+            "", "#", "123abc", "#1A2B3C", "#12345", "#1234567", "#12345g", "#+12345",
+        ] {
+            assert!(
+                schema::HairColor::from_str(invalid).is_err(),
+                "{:?} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        for (path, part, num_valid) in [
+            (PART_ONE_TEST_FILE_PATH, Part::One, 2),
+            (PART_TWO_VALIDS_FILE_PATH, Part::Two, 4),
+            (PART_TWO_INVALIDS_FILE_PATH, Part::Two, 0),
+        ] {
+            let input = fs::read_to_string(path).unwrap();
+            let records = Record::parse_batch(&input);
+            let reports =
+                Day4::explain(&records, &Schema::puzzle(part).unwrap(), CheckMode::Lenient);
+            assert_eq!(reports.len(), records.len());
+            let valid = reports.iter().filter(|report| report.is_valid()).count();
+            assert_eq!(valid, num_valid);
+        }
+
+        let input = fs::read_to_string(PART_ONE_TEST_FILE_PATH).unwrap();
+        let records = Record::parse_batch(&input);
+        let reports = Day4::explain(
+            &records,
+            &Schema::puzzle(Part::One).unwrap(),
+            CheckMode::Lenient,
+        );
+        let lines = reports.iter().map(|report| report.line).collect::<Vec<_>>();
+        assert_eq!(lines, vec![1, 4, 7, 12]);
+        assert_eq!(reports[1].missing, vec!["hgt"]);
+        assert_eq!(reports[3].missing, vec!["byr"]);
+
+        let histogram = Day4::failure_histogram(&reports);
+        assert_eq!(histogram.get("missing hgt"), Some(&1));
+        assert_eq!(histogram.get("missing byr"), Some(&1));
+    }
+
+    #[test]
+    fn test_explain_invalid_fields() {
+        let records = Record::parse_batch(
+            "eyr:1972 cid:100\nhcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926",
+        );
+        let reports = Day4::explain(
+            &records,
+            &Schema::puzzle(Part::Two).unwrap(),
+            CheckMode::Lenient,
+        );
+        let failures = reports[0]
+            .invalid
+            .iter()
+            .map(|failure| (failure.field.as_str(), failure.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(failures, vec![
+            ("eyr", "1972"),
+            ("hgt", "170"),
+            ("pid", "186cm")
+        ]);
+        assert_eq!(
+            reports[0].invalid[0].rule,
+            "eyr must be between 2020 and 2030"
+        );
+        assert!(reports[0].missing.is_empty());
+    }
+
+    #[test]
+    fn test_schema_file() {
+        let schema = Schema::load(SCHEMA_FILE_PATH).unwrap();
+        assert_eq!(schema, Schema::puzzle(Part::Two).unwrap());
+        let part_one = Schema::puzzle(Part::One).unwrap();
+        assert!(part_one.fields.iter().all(|field| field.rule.is_none()));
+        assert_eq!(part_one.fields.len(), schema.fields.len());
+
+        let json = serde_json::to_string(&schema).unwrap();
+        assert_eq!(serde_json::from_str::<Schema>(&json).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_rules() {
+        let schema = Schema::puzzle(Part::Two).unwrap();
+        let rule = |name: &str| {
+            schema
+                .fields
+                .iter()
+                .find(|field| field.name == name)
+                .and_then(|field| field.rule.clone())
+                .unwrap()
+        };
+        for (field, valid, invalid) in [
+            ("byr", "2002", "2003"),
+            ("hgt", "60in", "190in"),
+            ("hgt", "190cm", "190"),
+            ("hcl", "#123abc", "#123abz"),
+            ("ecl", "brn", "wat"),
+            ("pid", "000000001", "0123456789"),
+            ("pid", "000000001", "+12345678"),
+        ] {
+            assert!(
+                rule(field).check(field, valid).is_ok(),
+                "{} {}",
+                field,
+                valid
+            );
+            assert!(
+                rule(field).check(field, invalid).is_err(),
+                "{} {}",
+                field,
+                invalid
+            );
+        }
+
+        let err = rule("hgt").check("hgt", "190").unwrap_err();
+        assert_eq!(err.to_string(), "hgt must end in one of cm, in");
+        let err = Rule::Digits { count: 3 }.check("id", "12").unwrap_err();
+        assert_eq!(err.to_string(), "id must be 3 digits");
+    }
+
+    #[test]
+    fn test_strict_mode() {
+        let records = Record::parse_batch(
+            "byr:1990 iyr:2015 eyr:2025 hgt:180cm\nhcl:#123abc ecl:brn pid:000000001 byr:1991 \
+             foo:bar baz",
+        );
+        let schema = Schema::puzzle(Part::Two).unwrap();
+
+        let lenient = Day4::explain(&records, &schema, CheckMode::Lenient);
+        let failures = lenient[0]
+            .invalid
+            .iter()
+            .map(|failure| (failure.kind, failure.field.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(failures, vec![(FailureKind::Malformed, "baz")]);
+
+        let strict = Day4::explain(&records, &schema, CheckMode::Strict);
+        let failures = strict[0]
+            .invalid
+            .iter()
+            .map(|failure| {
+                (
+                    failure.kind,
+                    failure.field.as_str(),
+                    failure.line,
+                    failure.column,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(failures, vec![
+            (FailureKind::Duplicate, "byr", 2, 35),
+            (FailureKind::Unknown, "foo", 2, 44),
+            (FailureKind::Malformed, "baz", 2, 52),
+        ]);
+        assert_eq!(strict[0].invalid[0].rule, "already set on line 1, column 1");
+
+        let histogram = Day4::failure_histogram(&strict);
+        assert_eq!(histogram.get("duplicate byr"), Some(&1));
+        assert_eq!(histogram.get("unknown foo"), Some(&1));
+    }
+
+    #[test]
+    fn test_passport_id() {
+        let id = schema::PassportId::from_str("000000001").unwrap();
+        assert_eq!(id.as_str(), "000000001");
+        assert_eq!(id.to_string(), "000000001");
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"000000001\"");
+
+        for invalid in [
+            "",
+            "12345678",
+            "+12345678",
+            "-12345678",
+            " 12345678",
+            "0123456789",
+        ] {
+            assert!(
+                schema::PassportId::from_str(invalid).is_err(),
+                "{:?} should be rejected",
+                invalid
+            );
+        }
+        assert!(schema::PassportId::with_digits("0042", 4).is_ok());
+
+        let input = fs::read_to_string(PART_TWO_VALIDS_FILE_PATH).unwrap();
+        let records = Record::parse_batch(&input);
+        let schema = Schema::puzzle(Part::Two).unwrap();
+        let exported = Day4::passport_ids(&records, &schema, CheckMode::Lenient);
+        assert_eq!(exported.skipped, 0);
+        let ids = exported
+            .ids
+            .iter()
+            .map(|id| id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![
+            "087499704",
+            "896056539",
+            "545766238",
+            "093154719"
+        ]);
+    }
+
+    #[test]
+    fn test_passport_ids_skip_unchecked_pids() {
+        // Part One accepts any pid, so only digit strings of the puzzle's width
+        // are exported
+        let records = Record::parse_batch(
+            "byr:1 iyr:1 eyr:1 hgt:1 hcl:1 ecl:1 pid:012345678\n\nbyr:1 iyr:1 eyr:1 hgt:1 hcl:1 \
+             ecl:1 pid:173cm\n\nbyr:1 iyr:1 eyr:1 hgt:1 hcl:1 ecl:1",
+        );
+        let exported = Day4::passport_ids(
+            &records,
+            &Schema::puzzle(Part::One).unwrap(),
+            CheckMode::Lenient,
+        );
+        let ids = exported
+            .ids
+            .iter()
+            .map(|id| id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["012345678"]);
+        assert_eq!(exported.skipped, 1);
+    }
+
+    #[test]
+    fn test_blank_records() {
+        let record = "byr:1990 iyr:2015 eyr:2025 hgt:180cm\nhcl:#123abc ecl:brn pid:000000001";
+        let input = format!("{}\n\n\n\n\n{}\n\n\n", record, record);
+        let records = Record::parse_batch(&input);
+        let reports = Day4::explain(
+            &records,
+            &Schema::puzzle(Part::Two).unwrap(),
+            CheckMode::Lenient,
+        );
+        let lines = reports
+            .iter()
+            .map(|report| (report.index, report.line, report.is_valid()))
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![(1, 1, true), (2, 7, true)]);
+        assert!(Day4::failure_histogram(&reports).is_empty());
+    }
+
+    #[test]
+    fn test_tokens() {
+        let records = Record::parse_batch("\nbyr:1990  iyr:2015\nbad\n\n\n cid:1\n");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].line, 2);
+        assert_eq!(records[0].tokens, vec![
+            Token {
+                key:    "byr",
+                value:  Some("1990"),
+                line:   2,
+                column: 1,
+            },
+            Token {
+                key:    "iyr",
+                value:  Some("2015"),
+                line:   2,
+                column: 11,
+            },
+            Token {
+                key:    "bad",
+                value:  None,
+                line:   3,
+                column: 1,
+            },
+        ]);
+        assert_eq!(records[1].line, 6);
+        assert_eq!(records[1].get("cid"), Some("1"));
+        assert_eq!(records[1].tokens[0].column, 2);
     }
 }
@@ -1,48 +1,42 @@
-use std::cmp::max;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader};
-
 use anyhow::{anyhow, Context, Result};
 
-use crate::problem::{Part, Solved};
+use crate::problem::{Answer, Solved};
 
-const INPUT_FILE_PATH: &str = "src/day5/puzzle_inputs";
+const INPUT_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day5/puzzle_inputs");
 
+/// Day 5: Binary Boarding
 pub struct Day5;
 
-impl Day5 {
-    fn solve(problem_part: Part, input_file_path: &str) -> Result<u32> {
-        match problem_part {
-            Part::One => {
-                let file = File::open(input_file_path).context("Failed to open input file")?;
-                let reader = BufReader::new(file);
-                let mut max_id = u32::MIN;
-                for line in reader.lines() {
-                    let str = line?;
-                    let pass = boarding_pass::BoardingPass::new(&str);
-                    let seat_id = pass.parse_seat_id()?;
-                    max_id = max(max_id, seat_id);
-                }
-                Ok(max_id)
-            },
-            Part::Two => {
-                let inputs = fs::read_to_string(input_file_path)?;
-                let mut seat_ids = inputs
-                    .split('\n')
-                    .map(boarding_pass::BoardingPass::new)
-                    .filter_map(|p| p.parse_seat_id().ok())
-                    .collect::<Vec<_>>();
-                seat_ids.sort_unstable();
-                let seat_id_pairs_iter = IterByPair::new(Box::new(seat_ids.into_iter()));
-
-                for (first, second) in seat_id_pairs_iter {
-                    if second - first == 2 {
-                        return Ok(second - 1);
-                    }
-                }
-                Err(anyhow!("Failed to find unoccupied seat"))
-            },
+impl Solved for Day5 {
+    // sorted seat IDs
+    type Input<'a> = Vec<u32>;
+
+    const DAY: u8 = 5;
+    const INPUT_FILE_PATH: &'static str = INPUT_FILE_PATH;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        let mut seat_ids = input
+            .lines()
+            .map(|line| boarding_pass::BoardingPass::new(line).parse_seat_id())
+            .collect::<Result<Vec<_>>>()?;
+        seat_ids.sort_unstable();
+        Ok(seat_ids)
+    }
+
+    fn part_one(seat_ids: &Self::Input<'_>) -> Result<Answer> {
+        let max_id = seat_ids.last().context("No boarding passes in input")?;
+        Ok(Answer::from(*max_id))
+    }
+
+    fn part_two(seat_ids: &Self::Input<'_>) -> Result<Answer> {
+        let seat_id_pairs_iter = IterByPair::new(Box::new(seat_ids.iter().copied()));
+
+        for (first, second) in seat_id_pairs_iter {
+            if second - first == 2 {
+                return Ok(Answer::from(second - 1));
+            }
         }
+        Err(anyhow!("Failed to find unoccupied seat"))
     }
 }
 
@@ -50,18 +44,18 @@ impl Day5 {
 // just looked up values in my vec by index and index + 1, or even used a
 // Peekable -- but this was good practice, my first time implementing the
 // Iterator trait
-struct IterByPair<T: Copy> {
+struct IterByPair<'a, T: Copy> {
     next: Option<(T, T)>,
-    inner_iter: Box<dyn Iterator<Item = T>>,
+    inner_iter: Box<dyn Iterator<Item = T> + 'a>,
 }
 
-impl<T: Copy> IterByPair<T> {
-    fn new(inner_iter: Box<dyn Iterator<Item = T>>) -> Self {
+impl<'a, T: Copy> IterByPair<'a, T> {
+    fn new(inner_iter: Box<dyn Iterator<Item = T> + 'a>) -> Self {
         Self { next: None, inner_iter }
     }
 }
 
-impl<T: Copy> Iterator for IterByPair<T> {
+impl<T: Copy> Iterator for IterByPair<'_, T> {
     type Item = (T, T);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -80,7 +74,8 @@ impl<T: Copy> Iterator for IterByPair<T> {
     }
 }
 
-mod boarding_pass {
+/// Decoding of binary space partitioned boarding passes.
+pub mod boarding_pass {
     use anyhow::{anyhow, Result};
 
     const MIN_ROW: u8 = 0;
@@ -88,6 +83,7 @@ mod boarding_pass {
     const MIN_COL: u8 = 0;
     const MAX_COL: u8 = 7;
 
+    /// A boarding pass like `FBFBBFFRLR`.
     pub struct BoardingPass<'a>(&'a str);
 
     impl<'a> BoardingPass<'a> {
@@ -95,6 +91,7 @@ mod boarding_pass {
             Self(input)
         }
 
+        /// Decodes the seat ID, which is `row * 8 + column`.
         pub fn parse_seat_id(&self) -> Result<u32> {
             let row = self.parse_row()?;
             let col = self.parse_col()?;
@@ -107,8 +104,7 @@ mod boarding_pass {
             for char in self.0.chars() {
                 match char {
                     'F' => max_row = min_row + ((max_row - min_row) / 2),
-                    // the 'plus one' here is a bit of a hack to get ceiling division
-                    'B' => min_row = min_row + ((max_row - min_row + 1) / 2),
+                    'B' => min_row += (max_row - min_row).div_ceil(2),
                     _ => (),
                 }
             }
@@ -130,8 +126,7 @@ mod boarding_pass {
             for char in self.0.chars() {
                 match char {
                     'L' => max_col = min_col + ((max_col - min_col) / 2),
-                    // the 'plus one' here is a bit of a hack to get ceiling division
-                    'R' => min_col = min_col + ((max_col - min_col + 1) / 2),
+                    'R' => min_col += (max_col - min_col).div_ceil(2),
                     _ => (),
                 }
             }
@@ -149,22 +144,19 @@ mod boarding_pass {
     }
 }
 
-impl Solved for Day5 {
-    fn print_solution(part: Part) {
-        let solution = Self::solve(part, INPUT_FILE_PATH).unwrap();
-        println!("Day 5 {} solution: {}", part, solution);
-    }
-}
-
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
+    use crate::problem::Part;
 
     const TEST_FILE_PATH: &str = "src/day5/sample";
 
     #[test]
     fn test_part_one() {
-        let solution = Day5::solve(Part::One, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 820);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day5::solution(Part::One, &input).unwrap();
+        assert_eq!(solution, Answer::Int(820));
     }
 }
@@ -1,9 +1,8 @@
 use std::collections::HashSet;
-use std::fs;
 
 use anyhow::{anyhow, Context, Result};
 
-use crate::problem::{Part, Solved};
+use crate::problem::{Answer, Solved};
 
 // TODO(dkimbel): POTENTIAL IMPROVEMENTS
 //   - Reduce this to using only one Group type, and either have that track
@@ -11,107 +10,120 @@ use crate::problem::{Part, Solved};
 //     chars-to-counts along with a num-members-of-group int (so we can deduce
 //     which chars were present for every group member).
 
-const INPUT_FILE_PATH: &str = "src/day6/puzzle_inputs";
+const INPUT_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day6/puzzle_inputs");
 
+/// Day 6: Custom Customs
 pub struct Day6;
 
-impl Day6 {
-    fn solve(problem_part: Part, input_file_path: &str) -> Result<usize> {
-        let input = fs::read_to_string(input_file_path).context("Unable to read input file")?;
-        match problem_part {
-            Part::One => {
-                let groups = input.split("\n\n").map(UniqueGroup::new);
-                let summed_totals: usize = groups.map(|g| g.num_unique_answers()).sum();
-                Ok(summed_totals)
-            },
-            Part::Two => {
-                let groups = input.split("\n\n").map(ConsensusGroup::new);
-                let mut summed_totals = 0;
-                for maybe_group in groups {
-                    let group = maybe_group.context("All groups must be defined")?;
-                    summed_totals += group.num_shared_answers();
-                }
-                Ok(summed_totals)
-            },
+impl Solved for Day6 {
+    // each group's answers, as one set per person
+    type Input<'a> = Vec<Vec<HashSet<char>>>;
+
+    const DAY: u8 = 6;
+    const INPUT_FILE_PATH: &'static str = INPUT_FILE_PATH;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        let groups = input
+            .split("\n\n")
+            .map(|group| {
+                group
+                    .lines()
+                    .map(|person| person.trim())
+                    .filter(|person| !person.is_empty())
+                    .map(|person| person.chars().collect())
+                    .collect()
+            })
+            .collect();
+        Ok(groups)
+    }
+
+    fn part_one(groups: &Self::Input<'_>) -> Result<Answer> {
+        let groups = groups.iter().map(|group| UniqueGroup::new(group));
+        let summed_totals: usize = groups.map(|g| g.num_unique_answers()).sum();
+        Ok(Answer::from(summed_totals))
+    }
+
+    fn part_two(groups: &Self::Input<'_>) -> Result<Answer> {
+        let groups = groups.iter().map(|group| ConsensusGroup::new(group));
+        let mut summed_totals = 0;
+        for maybe_group in groups {
+            let group = maybe_group.context("All groups must be defined")?;
+            summed_totals += group.num_shared_answers();
         }
+        Ok(Answer::from(summed_totals))
     }
 }
 
-struct ConsensusGroup {
+/// A group's answers, keeping only those everyone answered yes to.
+pub struct ConsensusGroup {
     shared_answers: HashSet<char>,
 }
 
 impl ConsensusGroup {
-    fn new(input: &str) -> Result<Self> {
+    /// Tallies a group from each person's set of answers.
+    pub fn new(people: &[HashSet<char>]) -> Result<Self> {
         let mut maybe_shared_answers: Option<HashSet<char>> = None;
-        let cleaned_input = input
-            .split('\n')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty());
 
-        for individuals_answers in cleaned_input {
-            let individuals_set = individuals_answers.chars().collect::<HashSet<_>>();
+        for individuals_set in people {
             maybe_shared_answers = match maybe_shared_answers {
-                None => Some(individuals_set),
+                None => Some(individuals_set.clone()),
                 Some(shared_set) => {
-                    let intersect = shared_set.intersection(&individuals_set).copied();
+                    let intersect = shared_set.intersection(individuals_set).copied();
                     Some(intersect.collect::<HashSet<char>>())
                 },
             }
         }
 
-        let shared_answers = maybe_shared_answers.ok_or(anyhow!("Cleaned-up input was empty"))?;
+        let shared_answers = maybe_shared_answers.ok_or(anyhow!("Group has no answers"))?;
         Ok(Self { shared_answers })
     }
 
-    fn num_shared_answers(&self) -> usize {
+    pub fn num_shared_answers(&self) -> usize {
         self.shared_answers.len()
     }
 }
 
-struct UniqueGroup {
+/// A group's answers, keeping those anyone answered yes to.
+pub struct UniqueGroup {
     unique_answers: HashSet<char>,
 }
 
 impl UniqueGroup {
-    fn new(input: &str) -> Self {
+    /// Tallies a group from each person's set of answers.
+    pub fn new(people: &[HashSet<char>]) -> Self {
         let mut unique_answers = HashSet::<char>::new();
-        for individuals_answers in input.split('\n') {
-            for char in individuals_answers.trim().chars() {
-                unique_answers.insert(char);
-            }
+        for individuals_set in people {
+            unique_answers.extend(individuals_set);
         }
 
         Self { unique_answers }
     }
 
-    fn num_unique_answers(&self) -> usize {
+    pub fn num_unique_answers(&self) -> usize {
         self.unique_answers.len()
     }
 }
 
-impl Solved for Day6 {
-    fn print_solution(part: Part) {
-        let solution = Self::solve(part, INPUT_FILE_PATH).unwrap();
-        println!("Day 6 {} solution: {}", part, solution);
-    }
-}
-
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
+    use crate::problem::Part;
 
     const TEST_FILE_PATH: &str = "src/day6/sample";
 
     #[test]
     fn test_part_one() {
-        let solution = Day6::solve(Part::One, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 11);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day6::solution(Part::One, &input).unwrap();
+        assert_eq!(solution, Answer::Int(11));
     }
 
     #[test]
     fn test_part_two() {
-        let solution = Day6::solve(Part::Two, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 6);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day6::solution(Part::Two, &input).unwrap();
+        assert_eq!(solution, Answer::Int(6));
     }
 }
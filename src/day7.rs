@@ -1,37 +1,48 @@
 use std::collections::HashMap;
-use std::fs;
 
 use anyhow::{Context, Result};
 use regex::Regex;
 
-use crate::problem::{Part, Solved};
+use crate::problem::{Answer, Solved};
 
 // TODO(dkimbel): POTENTIAL IMPROVEMENTS
 //   - don't compile the same regex repeatedly in Bag::new
 //   - make can_contain_color and num_inner_bags return a Result type so
 //     there's an error if the given color can't be found?
 
-const INPUT_FILE_PATH: &str = "src/day7/puzzle_inputs";
+const INPUT_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day7/puzzle_inputs");
 
+/// Day 7: Handy Haversacks
 pub struct Day7;
 
-impl Day7 {
-    fn solve(problem_part: Part, input_file_path: &str) -> Result<usize> {
-        let input = fs::read_to_string(input_file_path)?;
-        let bags = Bags::new(&input)?;
-        match problem_part {
-            Part::One => Ok(bags.num_colors_holding_color("shiny gold")),
-            Part::Two => Ok(bags.num_inner_bags("shiny gold")),
-        }
+impl Solved for Day7 {
+    type Input<'a> = Bags<'a>;
+
+    const DAY: u8 = 7;
+    const INPUT_FILE_PATH: &'static str = INPUT_FILE_PATH;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Bags::new(input)
+    }
+
+    fn part_one(bags: &Self::Input<'_>) -> Result<Answer> {
+        Ok(Answer::from(bags.num_colors_holding_color("shiny gold")))
+    }
+
+    fn part_two(bags: &Self::Input<'_>) -> Result<Answer> {
+        Ok(Answer::from(bags.num_inner_bags("shiny gold")))
     }
 }
 
-struct Bags<'a> {
+/// The bag rules, keyed by outer bag color.
+pub struct Bags<'a> {
     by_color: HashMap<&'a str, Vec<BagContent<'a>>>,
 }
 
 impl<'a> Bags<'a> {
-    fn new(input: &'a str) -> Result<Self> {
+    /// Parses one rule per line, like
+    /// `light red bags contain 1 bright white bag, 2 muted yellow bags.`
+    pub fn new(input: &'a str) -> Result<Self> {
         let by_color: HashMap<_, _> = input
             .lines()
             .map(Bag::new)
@@ -40,7 +51,8 @@ impl<'a> Bags<'a> {
         Ok(Self { by_color })
     }
 
-    fn can_contain_color(&self, outer_color: &'a str, inner_color: &'a str) -> bool {
+    /// Whether an `outer_color` bag can (eventually) hold an `inner_color` bag.
+    pub fn can_contain_color(&self, outer_color: &'a str, inner_color: &'a str) -> bool {
         // base case
         if outer_color == inner_color {
             return true;
@@ -57,7 +69,8 @@ impl<'a> Bags<'a> {
             .any(|color| self.can_contain_color(color, inner_color))
     }
 
-    fn num_colors_holding_color(&self, search_color: &str) -> usize {
+    /// Counts the other colors that can eventually hold a `search_color` bag.
+    pub fn num_colors_holding_color(&self, search_color: &str) -> usize {
         self.by_color
             .iter()
             .map(|(&color, _contents)| color)
@@ -65,7 +78,8 @@ impl<'a> Bags<'a> {
             .count()
     }
 
-    fn num_inner_bags(&self, color: &str) -> usize {
+    /// Counts every bag nested inside a `color` bag.
+    pub fn num_inner_bags(&self, color: &str) -> usize {
         let contents = match self.by_color.get(color) {
             Some(contents) => contents,
             None => return 0,
@@ -122,35 +136,34 @@ struct BagContent<'a> {
     count: u8,
 }
 
-impl Solved for Day7 {
-    fn print_solution(part: Part) {
-        let solution = Self::solve(part, INPUT_FILE_PATH).unwrap();
-        println!("Day 7 {} solution: {}", part, solution);
-    }
-}
-
 #[cfg(test)]
 mod tests {
+    use std::fs;
+
     use super::*;
+    use crate::problem::Part;
 
     const TEST_FILE_PATH: &str = "src/day7/sample";
     const TEST_FILE_PATH_LARGER: &str = "src/day7/sample_larger";
 
     #[test]
     fn test_part_one() {
-        let solution = Day7::solve(Part::One, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 4);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day7::solution(Part::One, &input).unwrap();
+        assert_eq!(solution, Answer::Int(4));
     }
 
     #[test]
     fn test_part_two_small() {
-        let solution = Day7::solve(Part::Two, TEST_FILE_PATH).unwrap();
-        assert_eq!(solution, 32);
+        let input = fs::read_to_string(TEST_FILE_PATH).unwrap();
+        let solution = Day7::solution(Part::Two, &input).unwrap();
+        assert_eq!(solution, Answer::Int(32));
     }
 
     #[test]
     fn test_part_two_large() {
-        let solution = Day7::solve(Part::Two, TEST_FILE_PATH_LARGER).unwrap();
-        assert_eq!(solution, 126);
+        let input = fs::read_to_string(TEST_FILE_PATH_LARGER).unwrap();
+        let solution = Day7::solution(Part::Two, &input).unwrap();
+        assert_eq!(solution, Answer::Int(126));
     }
 }
@@ -0,0 +1,246 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+
+/// A rectangular 2D grid of cells, stored row by row. `x` indexes columns from
+/// the left and `y` indexes rows from the top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width:  usize,
+    height: usize,
+    cells:  Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from cells listed row by row.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Self> {
+        let expected_len = width
+            .checked_mul(height)
+            .ok_or_else(|| anyhow!("Grid of {}x{} cells is too large", width, height))?;
+        if cells.len() != expected_len {
+            return Err(anyhow!(
+                "Grid of {}x{} cells needs {} cells, got {}",
+                width,
+                height,
+                expected_len,
+                cells.len()
+            ));
+        }
+        Ok(Self { width, height, cells })
+    }
+
+    /// Builds a grid from rows, which must all be the same width.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(anyhow!(
+                    "Row {} has {} cells, but row 1 has {}",
+                    index + 1,
+                    row.len(),
+                    width
+                ));
+            }
+            cells.extend(row);
+        }
+        Self::new(width, height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            self.cells.get_mut(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Treats the grid as repeating forever in both directions; only `None`
+    /// for an empty grid.
+    pub fn get_wrapping(&self, x: usize, y: usize) -> Option<&T> {
+        if self.is_empty() {
+            return None;
+        }
+        self.get(x % self.width, y % self.height)
+    }
+
+    /// The cells surrounding `(x, y)`, including diagonals, along with their
+    /// coordinates. Cells past the edges are skipped rather than wrapped.
+    pub fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        const OFFSETS: [(isize, isize); 8] = [
+            (-1, -1),
+            (0, -1),
+            (1, -1),
+            (-1, 0),
+            (1, 0),
+            (-1, 1),
+            (0, 1),
+            (1, 1),
+        ];
+        OFFSETS.iter().filter_map(move |&(dx, dy)| {
+            let neighbor_x = x.checked_add_signed(dx)?;
+            let neighbor_y = y.checked_add_signed(dy)?;
+            let cell = self.get(neighbor_x, neighbor_y)?;
+            Some(((neighbor_x, neighbor_y), cell))
+        })
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).filter_map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+        if x < self.width {
+            Some(self.cells.iter().skip(x).step_by(self.width))
+        } else {
+            None
+        }
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).filter_map(|x| self.column(x))
+    }
+
+    /// Parses a map with one row of cells per line, converting each char with
+    /// `parse_cell`.
+    pub fn parse_with(input: &str, mut parse_cell: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for (index, line) in input.lines().enumerate() {
+            let row = line
+                .chars()
+                .map(&mut parse_cell)
+                .collect::<Result<Vec<_>>>()
+                .map_err(|err| err.context(anyhow!("Failed to parse line {}", index + 1)))?;
+            if let Some(first_row) = rows.first() {
+                if row.len() != first_row.len() {
+                    return Err(anyhow!(
+                        "Line {} has {} cells, but line 1 has {}",
+                        index + 1,
+                        row.len(),
+                        first_row.len()
+                    ));
+                }
+            }
+            rows.push(row);
+        }
+        Self::from_rows(rows)
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: TryFrom<char>,
+    T::Error: Into<anyhow::Error>,
+{
+    /// Parses a map with one row of cells per line, one cell per char.
+    pub fn parse(input: &str) -> Result<Self> {
+        Self::parse_with(input, |char| T::try_from(char).map_err(Into::into))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Digit(u32);
+
+    impl TryFrom<char> for Digit {
+        type Error = anyhow::Error;
+
+        fn try_from(char: char) -> Result<Self> {
+            char.to_digit(10)
+                .map(Digit)
+                .ok_or_else(|| anyhow!("Not a digit: {}", char))
+        }
+    }
+
+    impl fmt::Display for Digit {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    #[test]
+    fn test_accessors() {
+        let grid = Grid::<Digit>::parse("123\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(2, 1), Some(&Digit(6)));
+        assert_eq!(grid.get(3, 1), None);
+        assert_eq!(grid.get_wrapping(3, 3), Some(&Digit(4)));
+        assert_eq!(grid.row(1).unwrap(), &[Digit(4), Digit(5), Digit(6)]);
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        let columns = grid
+            .columns()
+            .map(|column| column.map(|digit| digit.0).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+
+        let neighbors = grid
+            .neighbors(0, 0)
+            .map(|(_, digit)| digit.0)
+            .collect::<Vec<_>>();
+        assert_eq!(neighbors, vec![2, 4, 5]);
+        assert_eq!(grid.neighbors(1, 1).count(), 5);
+    }
+
+    #[test]
+    fn test_invalid_grids() {
+        let err = Grid::<Digit>::parse("123\n45\n").unwrap_err();
+        assert_eq!(err.to_string(), "Line 2 has 2 cells, but line 1 has 3");
+
+        let err = Grid::<Digit>::parse("123\n4x6\n").unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "Failed to parse line 2: Not a digit: x"
+        );
+
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+        assert!(Grid::<Digit>::parse("")
+            .unwrap()
+            .get_wrapping(0, 0)
+            .is_none());
+    }
+}
@@ -0,0 +1,18 @@
+//! Solutions for the 2020 Advent of Code.
+//!
+//! Each `dayN` module exposes a `DayN` type implementing [`problem::Solved`],
+//! along with the domain types used to solve that day's puzzle. The
+//! [`registry`] lists every Day so they can be looked up at runtime.
+
+pub mod bench;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod grid;
+pub mod problem;
+pub mod registry;
+pub mod verify;
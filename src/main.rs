@@ -1,15 +1,601 @@
-use day7::Day7;
-use problem::{Part, Solved};
-
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod problem;
-
-fn main() {
-    Day7::print_solution(Part::Two);
+use std::fs;
+use std::str::FromStr;
+
+use advent2020::bench::{bench_solver, BenchReport, Stats};
+use advent2020::day2::{
+    Day2, Normalization, ParseMode, PasswordDatabase, PositionUnit, TextOptions,
+};
+use advent2020::day3::{render, Day3, MapConfig, PathAnalyzer, Slope};
+use advent2020::day4::schema::Schema;
+use advent2020::day4::{CheckMode, Day4, Record};
+use advent2020::problem::{checked_product, InputSource, Part, Solved};
+use advent2020::registry::{find_solver, Solver, SOLVERS};
+use advent2020::verify::{verify_solver, RecordedAnswers, Verdict, ANSWERS_FILE_PATH};
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+#[derive(Parser)]
+#[command(about = "Solutions for the 2020 Advent of Code")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solutions for one or more days
+    Run {
+        #[command(flatten)]
+        selection: Selection,
+        /// Input file to solve instead of the checked-in puzzle input, or `-`
+        /// for stdin. Only allowed when running a single day.
+        #[arg(long)]
+        input:     Option<InputSource>,
+    },
+    /// Time reading, parsing and solving the checked-in puzzle inputs
+    Bench {
+        #[command(flatten)]
+        selection:  Selection,
+        /// Number of times to run each part
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format:     Format,
+    },
+    /// Check the checked-in puzzle inputs' answers against recorded ones
+    Verify {
+        #[command(flatten)]
+        selection: Selection,
+        /// Answers file to check against
+        #[arg(long, default_value = ANSWERS_FILE_PATH)]
+        answers:   String,
+        /// Record the current answers as the new baseline instead of checking
+        #[arg(long)]
+        record:    bool,
+    },
+    /// Explain why each password in a Day 2 database fails its policy
+    Violations {
+        /// Which part's rules to check against
+        #[arg(long, default_value = "1")]
+        part:      Part,
+        /// Password database to check instead of the checked-in puzzle input,
+        /// or `-` for stdin
+        #[arg(long)]
+        input:     Option<InputSource>,
+        /// Skip malformed lines, summarizing them at the end, instead of
+        /// failing on the first one
+        #[arg(long)]
+        lenient:   bool,
+        /// Count positions in grapheme clusters rather than code points
+        #[arg(long)]
+        graphemes: bool,
+        /// Normalize passwords and policy characters before checking (none,
+        /// nfc or nfkc)
+        #[arg(long, default_value = "none")]
+        normalize: Normalization,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format:    Format,
+    },
+    /// Rank every Day 3 slope within some bounds by the trees it hits
+    Slopes {
+        /// Largest number of tiles to move right per step
+        #[arg(long, default_value_t = 7)]
+        max_right: usize,
+        /// Largest number of tiles to move down per step
+        #[arg(long, default_value_t = 2)]
+        max_down:  usize,
+        #[command(flatten)]
+        map:       MapOptions,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format:    Format,
+    },
+    /// Draw the Day 3 map with the tiles a slope visits marked
+    Render {
+        /// Slope to draw, like `3,1` for 3 right and 1 down; repeat to draw
+        /// several paths in the SVG
+        #[arg(long = "slope", default_value = "3,1")]
+        slopes: Vec<Slope>,
+        #[command(flatten)]
+        map:    MapOptions,
+        /// Write an SVG image to this path instead of printing ASCII
+        #[arg(long)]
+        svg:    Option<String>,
+    },
+    /// Count the trees a Day 3 map's slopes hit, and their product
+    Trees {
+        /// Slope to check, like `3,1` for 3 right and 1 down; repeat for
+        /// several. Defaults to the config file's slopes, or the puzzle's.
+        #[arg(long = "slope")]
+        slopes: Vec<Slope>,
+        /// Descend this many rows, repeating the map downward as well as to
+        /// the right, instead of stopping at its bottom
+        #[arg(long)]
+        rows:   Option<u64>,
+        #[command(flatten)]
+        map:    MapOptions,
+    },
+    /// Check a Day 4 passport batch, optionally explaining each invalid record
+    Passports {
+        /// Which part's rules to check against
+        #[arg(long, default_value = "2")]
+        part:       Part,
+        /// TOML or JSON schema file to check against instead of `--part`'s
+        /// rules
+        #[arg(long)]
+        schema:     Option<String>,
+        /// Batch to check instead of the checked-in puzzle input, or `-` for
+        /// stdin
+        #[arg(long)]
+        input:      Option<InputSource>,
+        /// List every invalid record's missing and failing fields, and how
+        /// often each reason comes up
+        #[arg(long)]
+        explain:    bool,
+        /// Also reject records that repeat a key or use one the schema doesn't
+        /// list
+        #[arg(long)]
+        strict:     bool,
+        /// Write the passport ID of each valid record to this path, one per
+        /// line
+        #[arg(long)]
+        export_ids: Option<String>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format:     Format,
+    },
+}
+
+/// Where to read a Day 3 map from, and how.
+#[derive(Args)]
+struct MapOptions {
+    /// Map to use instead of the checked-in puzzle input, or `-` for stdin
+    #[arg(long)]
+    input:  Option<InputSource>,
+    /// JSON file with `slopes`, `trees` and `open` keys
+    #[arg(long)]
+    config: Option<String>,
+    /// Chars that mark trees, overriding the config file
+    #[arg(long)]
+    trees:  Option<String>,
+    /// Chars that mark open ground, overriding the config file
+    #[arg(long)]
+    open:   Option<String>,
+}
+
+impl MapOptions {
+    fn load(&self) -> Result<(PathAnalyzer, MapConfig)> {
+        let mut config = match &self.config {
+            Some(path) => MapConfig::load(path)?,
+            None => MapConfig::default(),
+        };
+        if let Some(trees) = &self.trees {
+            config.alphabet.trees = trees.clone();
+        }
+        if let Some(open) = &self.open {
+            config.alphabet.open = open.clone();
+        }
+        let input = self
+            .input
+            .clone()
+            .unwrap_or(InputSource::Default)
+            .read_to_string(Day3::INPUT_FILE_PATH)?;
+        let analyzer = PathAnalyzer::with_alphabet(&input, &config.alphabet)?;
+        Ok((analyzer, config))
+    }
+}
+
+#[derive(Args)]
+struct Selection {
+    /// Day(s) to run, like `4`, `1-5` or `1,3,5-7`
+    #[arg(long, conflicts_with = "all")]
+    day:  Option<DaySelection>,
+    /// Run every available day; the default for `bench` and `verify`
+    #[arg(long)]
+    all:  bool,
+    /// Part to run (1 or 2); runs both parts if omitted
+    #[arg(long)]
+    part: Option<Part>,
+}
+
+impl Selection {
+    // `run` prints every answer, so it makes the caller ask for all of them
+    fn require_explicit(&self) -> Result<()> {
+        if self.day.is_none() && !self.all {
+            return Err(anyhow!(
+                "Choose days to run with --day, or every day with --all"
+            ));
+        }
+        Ok(())
+    }
+
+    fn solvers(&self) -> Result<Vec<&'static Solver>> {
+        match &self.day {
+            Some(DaySelection(days)) if !self.all => days
+                .iter()
+                .map(|&day| find_solver(day).context(anyhow!("No solution for day {}", day)))
+                .collect(),
+            _ => Ok(SOLVERS.iter().collect()),
+        }
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DaySelection(Vec<u8>);
+
+impl FromStr for DaySelection {
+    type Err = anyhow::Error;
+
+    // expects a string like "1,3,5-7"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = Vec::new();
+        for item in s.split(',') {
+            match item.split_once('-') {
+                Some((first_input, last_input)) => {
+                    let first = parse_day(first_input)?;
+                    let last = parse_day(last_input)?;
+                    if first > last {
+                        return Err(anyhow!("Day range {} must not run backwards", item));
+                    }
+                    days.extend(first..=last);
+                },
+                None => days.push(parse_day(item)?),
+            }
+        }
+        Ok(Self(days))
+    }
+}
+
+fn parse_day(input: &str) -> Result<u8> {
+    input
+        .trim()
+        .parse::<u8>()
+        .context(anyhow!("Could not parse day from {}", input))
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Run { selection, input } => run(&selection, input),
+        Command::Bench { selection, iterations, format } => bench(&selection, iterations, format),
+        Command::Verify { selection, answers, record } => verify(&selection, &answers, record),
+        Command::Violations {
+            part,
+            input,
+            lenient,
+            graphemes,
+            normalize,
+            format,
+        } => {
+            let mode = if lenient { ParseMode::Lenient } else { ParseMode::Strict };
+            let text = TextOptions {
+                positions:     if graphemes {
+                    PositionUnit::Grapheme
+                } else {
+                    PositionUnit::CodePoint
+                },
+                normalization: normalize,
+            };
+            violations(part, input, mode, text, format)
+        },
+        Command::Slopes { max_right, max_down, map, format } => {
+            slopes(max_right, max_down, &map, format)
+        },
+        Command::Render { slopes, map, svg } => render(&slopes, &map, svg.as_deref()),
+        Command::Trees { slopes, rows, map } => trees(&slopes, rows, &map),
+        Command::Passports {
+            part,
+            schema,
+            input,
+            explain,
+            strict,
+            export_ids,
+            format,
+        } => {
+            let schema = match schema {
+                Some(path) => Schema::load(&path)?,
+                None => Schema::puzzle(part)?,
+            };
+            let mode = if strict { CheckMode::Strict } else { CheckMode::Lenient };
+            passports(&schema, input, explain, mode, export_ids.as_deref(), format)
+        },
+    }
+}
+
+fn run(selection: &Selection, input: Option<InputSource>) -> Result<()> {
+    selection.require_explicit()?;
+    let solvers = selection.solvers()?;
+    if input.is_some() && solvers.len() != 1 {
+        return Err(anyhow!(
+            "--input can only be used when running a single day"
+        ));
+    }
+    let source = input.unwrap_or(InputSource::Default);
+    let parts = selection.parts();
+
+    let mut num_failures = 0;
+    for solver in solvers {
+        let answers = source
+            .read_to_string(solver.input_file_path)
+            .and_then(|input| (solver.solutions)(&parts, &input));
+        let answers = match answers {
+            Ok(answers) => answers,
+            Err(err) => {
+                eprintln!("Day {} failed: {:#}", solver.day, err);
+                num_failures += 1;
+                continue;
+            },
+        };
+        for (part, answer) in parts.iter().zip(answers) {
+            match answer {
+                Ok(answer) => println!("Day {} {} solution: {}", solver.day, part, answer),
+                Err(err) => {
+                    eprintln!("Day {} {} failed: {:#}", solver.day, part, err);
+                    num_failures += 1;
+                },
+            }
+        }
+    }
+    if num_failures > 0 {
+        return Err(anyhow!("{} solution(s) failed", num_failures));
+    }
+    Ok(())
+}
+
+fn bench(selection: &Selection, iterations: usize, format: Format) -> Result<()> {
+    let mut reports = Vec::new();
+    for solver in selection.solvers()? {
+        for part in selection.parts() {
+            reports.push(bench_solver(solver, part, iterations)?);
+        }
+    }
+
+    match format {
+        Format::Table => print_bench_table(&reports),
+        Format::Json => println!("{}", serde_json::to_string_pretty(&reports)?),
+    }
+    Ok(())
+}
+
+fn print_bench_table(reports: &[BenchReport]) {
+    println!(
+        "{:<12} {:>10} {:>10} {:>10}   {:>10} {:>10} {:>10}   {:>10} {:>10} {:>10}",
+        "", "read min", "median", "max", "parse min", "median", "max", "solve min", "median", "max"
+    );
+    for report in reports {
+        println!(
+            "{:<12} {}   {}   {}",
+            format!("Day {} Part {}", report.day, report.part),
+            format_stats(&report.read),
+            format_stats(&report.parse),
+            format_stats(&report.solve)
+        );
+    }
+}
+
+fn format_stats(stats: &Stats) -> String {
+    format!(
+        "{:>10} {:>10} {:>10}",
+        format!("{:.1?}", stats.min),
+        format!("{:.1?}", stats.median),
+        format!("{:.1?}", stats.max)
+    )
+}
+
+fn verify(selection: &Selection, answers_path: &str, record: bool) -> Result<()> {
+    let mut recorded = RecordedAnswers::load(answers_path)?;
+    let mut num_failures = 0;
+    for solver in selection.solvers()? {
+        for part in selection.parts() {
+            let verdict = verify_solver(solver, part, &recorded);
+            println!("Day {} {}: {}", solver.day, part, verdict);
+            match verdict {
+                Verdict::Pass => (),
+                Verdict::Fail { actual, .. } | Verdict::Missing { actual } if record => {
+                    recorded.set(solver.day, part, actual);
+                },
+                _ => num_failures += 1,
+            }
+        }
+    }
+
+    if record {
+        recorded.save(answers_path)?;
+        println!("Recorded answers to {}", answers_path);
+    }
+    if num_failures > 0 {
+        return Err(anyhow!("{} answer(s) did not verify", num_failures));
+    }
+    Ok(())
+}
+
+fn violations(
+    part: Part,
+    input: Option<InputSource>,
+    mode: ParseMode,
+    text: TextOptions,
+    format: Format,
+) -> Result<()> {
+    let input = input
+        .unwrap_or(InputSource::Default)
+        .read_to_string(Day2::INPUT_FILE_PATH)?;
+    let database = PasswordDatabase::parse(&input, mode)?;
+    let violations = Day2::violations(&database, part, text)?;
+
+    match format {
+        Format::Table => {
+            for violation in &violations {
+                println!("{}", violation);
+            }
+            println!(
+                "{} of {} password(s) invalid",
+                violations.len(),
+                database.entries().len()
+            );
+        },
+        Format::Json => println!("{}", serde_json::to_string_pretty(&violations)?),
+    }
+
+    let errors = database.errors();
+    if !errors.is_empty() {
+        eprintln!("Skipped {} malformed line(s):", errors.len());
+        for error in errors {
+            eprintln!("  {}", error);
+        }
+    }
+    Ok(())
+}
+
+fn slopes(max_right: usize, max_down: usize, map: &MapOptions, format: Format) -> Result<()> {
+    let (analyzer, _) = map.load()?;
+    let search = analyzer.search_slopes(max_right, max_down)?;
+
+    match format {
+        Format::Table => {
+            let (Some(fewest), Some(most)) = (search.fewest(), search.most()) else {
+                println!("No slopes to search");
+                return Ok(());
+            };
+            println!(
+                "Fewest trees: {} ({} trees)",
+                fewest.slope, fewest.num_trees
+            );
+            println!("Most trees:   {} ({} trees)", most.slope, most.num_trees);
+            println!();
+            println!("{:>8} {:>8}", "trees", "slopes");
+            for (num_trees, num_slopes) in search.distribution() {
+                println!("{:>8} {:>8}", num_trees, num_slopes);
+            }
+            println!();
+            for count in &search.ranked {
+                println!("{:>8} {}", count.num_trees, count.slope);
+            }
+        },
+        Format::Json => println!("{}", serde_json::to_string_pretty(&search)?),
+    }
+    Ok(())
+}
+
+fn render(slopes: &[Slope], map: &MapOptions, svg_path: Option<&str>) -> Result<()> {
+    let (analyzer, _) = map.load()?;
+
+    match svg_path {
+        Some(path) => {
+            let svg = render::svg(&analyzer, slopes)?;
+            fs::write(path, svg).context(anyhow!("Failed to write SVG to {}", path))?;
+            println!("Wrote {} path(s) to {}", slopes.len(), path);
+        },
+        None => {
+            for &slope in slopes {
+                println!("Slope {}:", slope);
+                print!("{}", render::ascii(&analyzer, slope)?);
+            }
+        },
+    }
+    Ok(())
+}
+
+fn trees(slopes: &[Slope], rows: Option<u64>, map: &MapOptions) -> Result<()> {
+    let (analyzer, config) = map.load()?;
+    let slopes = if slopes.is_empty() { &config.slopes } else { slopes };
+    let mut tree_counts = Vec::new();
+    for &slope in slopes {
+        let num_trees = match rows {
+            Some(rows) => analyzer.calculate_num_trees_repeating(slope, rows)?,
+            None => analyzer.calculate_num_trees(slope)? as u64,
+        };
+        println!("{}: {} trees", slope, num_trees);
+        tree_counts.push(num_trees);
+    }
+    println!("Product: {}", checked_product(tree_counts)?);
+    Ok(())
+}
+
+fn passports(
+    schema: &Schema,
+    input: Option<InputSource>,
+    explain: bool,
+    mode: CheckMode,
+    export_path: Option<&str>,
+    format: Format,
+) -> Result<()> {
+    let input = input
+        .unwrap_or(InputSource::Default)
+        .read_to_string(Day4::INPUT_FILE_PATH)?;
+    let records = Record::parse_batch(&input);
+    let reports = Day4::explain(&records, schema, mode);
+
+    // export before printing anything, so a failed write can't follow a report
+    let exported = match export_path {
+        Some(path) => {
+            let exported = Day4::passport_ids(&records, schema, mode);
+            let lines = exported
+                .ids
+                .iter()
+                .map(|id| format!("{}\n", id))
+                .collect::<String>();
+            fs::write(path, lines).context(anyhow!("Failed to write passport IDs to {}", path))?;
+            Some((path, exported))
+        },
+        None => None,
+    };
+    let invalid = reports
+        .iter()
+        .filter(|report| !report.is_valid())
+        .collect::<Vec<_>>();
+    let histogram = Day4::failure_histogram(&reports);
+
+    match format {
+        Format::Table => {
+            if explain {
+                for report in &invalid {
+                    println!("{}", report);
+                }
+                println!();
+                for (reason, count) in &histogram {
+                    println!("{:>6} {}", count, reason);
+                }
+                println!();
+            }
+            println!(
+                "{} of {} record(s) valid",
+                reports.len() - invalid.len(),
+                reports.len()
+            );
+        },
+        Format::Json => {
+            let json = if explain {
+                serde_json::json!({ "invalid": invalid, "histogram": histogram })
+            } else {
+                serde_json::json!({ "valid": reports.len() - invalid.len(), "total": reports.len() })
+            };
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        },
+    }
+
+    if let Some((path, exported)) = exported {
+        let summary = format!(
+            "Wrote {} passport ID(s) to {}, skipping {} valid record(s) with a malformed pid",
+            exported.ids.len(),
+            path,
+            exported.skipped
+        );
+        // keep stdout parseable when it's JSON
+        match format {
+            Format::Table => println!("{}", summary),
+            Format::Json => eprintln!("{}", summary),
+        }
+    }
+    Ok(())
 }
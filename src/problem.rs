@@ -1,22 +1,201 @@
 use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::str::FromStr;
 
-#[allow(dead_code)] // typically only one variant is used at a time
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Which half of a day's puzzle to solve.
 #[derive(Debug, Copy, Clone)]
 pub enum Part {
     One,
     Two,
 }
 
-impl fmt::Display for Part {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let as_int = match self {
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn as_int(&self) -> u8 {
+        match self {
             Self::One => 1,
             Self::Two => 2,
-        };
-        write!(f, "Part {}", as_int)
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Part {}", self.as_int())
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" | "one" => Ok(Self::One),
+            "2" | "two" => Ok(Self::Two),
+            _ => Err(anyhow!("Invalid part {}; expected 1 or 2", s)),
+        }
+    }
+}
+
+/// A puzzle solution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Int(u64),
+    Str(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(int) => write!(f, "{}", int),
+            Self::Str(str) => write!(f, "{}", str),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(int: u32) -> Self {
+        Self::Int(u64::from(int))
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(int: u64) -> Self {
+        Self::Int(int)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(int: usize) -> Self {
+        // usize is at most 64 bits on every platform we build for
+        Self::Int(int as u64)
     }
 }
 
+impl From<String> for Answer {
+    fn from(str: String) -> Self {
+        Self::Str(str)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(str: &str) -> Self {
+        Self::Str(String::from(str))
+    }
+}
+
+/// Arithmetic on puzzle values that would otherwise silently wrap in release
+/// builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArithmeticError {
+    /// The product of `values` doesn't fit in a u64.
+    Overflow { values: Vec<u64> },
+}
+
+impl fmt::Display for ArithmeticError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow { values } => write!(f, "Product of {:?} overflows u64", values),
+        }
+    }
+}
+
+impl std::error::Error for ArithmeticError {}
+
+/// Multiplies `values` together, failing rather than wrapping on overflow.
+pub fn checked_product(values: impl IntoIterator<Item = u64>) -> Result<u64, ArithmeticError> {
+    let values = values.into_iter().collect::<Vec<_>>();
+    values
+        .iter()
+        .try_fold(1_u64, |acc, &value| acc.checked_mul(value))
+        .ok_or(ArithmeticError::Overflow { values })
+}
+
+/// Where to read a Day's puzzle input from; `Default` means the Day's own
+/// checked-in puzzle_inputs file.
+#[derive(Debug, Clone)]
+pub enum InputSource {
+    Default,
+    Path(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    /// Reads the whole input, falling back to `default_path` for `Default`.
+    pub fn read_to_string(&self, default_path: &str) -> Result<String> {
+        match self {
+            Self::Default => fs::read_to_string(default_path)
+                .context(anyhow!("Failed to read input file {}", default_path)),
+            Self::Path(path) => fs::read_to_string(path)
+                .context(anyhow!("Failed to read input file {}", path.display())),
+            Self::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed to read input from stdin")?;
+                Ok(input)
+            },
+        }
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = anyhow::Error;
+
+    // "-" means stdin, anything else is a file path
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "-" => Ok(Self::Stdin),
+            _ => Ok(Self::Path(PathBuf::from(s))),
+        }
+    }
+}
+
+/// One answer (or failure) per requested part, in the order requested.
+pub type PartAnswers = Vec<Result<Answer>>;
+
+/// A solved day of the Advent of Code. The input is parsed once, and then
+/// both parts are solved from the parsed form.
 pub trait Solved {
-    fn print_solution(part: Part);
+    const DAY: u8;
+    /// The checked-in puzzle input for this day.
+    const INPUT_FILE_PATH: &'static str;
+
+    /// The parsed puzzle input, which may borrow from the input text.
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>>;
+
+    fn part_one(input: &Self::Input<'_>) -> Result<Answer>;
+
+    fn part_two(input: &Self::Input<'_>) -> Result<Answer>;
+
+    fn solve_part(part: Part, input: &Self::Input<'_>) -> Result<Answer> {
+        match part {
+            Part::One => Self::part_one(input),
+            Part::Two => Self::part_two(input),
+        }
+    }
+
+    /// Parses the input text, then solves one part of the puzzle.
+    fn solution(part: Part, input: &str) -> Result<Answer> {
+        Self::solve_part(part, &Self::parse(input)?)
+    }
+
+    /// Parses the input text once, then solves each of `parts`. Fails outright
+    /// only if parsing fails.
+    fn solutions(parts: &[Part], input: &str) -> Result<PartAnswers> {
+        let input = Self::parse(input)?;
+        Ok(parts
+            .iter()
+            .map(|&part| Self::solve_part(part, &input))
+            .collect())
+    }
 }
@@ -0,0 +1,50 @@
+use anyhow::Result;
+
+use crate::bench::{time_phases, PhaseTimings};
+use crate::day1::Day1;
+use crate::day2::Day2;
+use crate::day3::Day3;
+use crate::day4::Day4;
+use crate::day5::Day5;
+use crate::day6::Day6;
+use crate::day7::Day7;
+use crate::problem::{Answer, Part, PartAnswers, Solved};
+
+/// Every solved Day, in day order. A Day needs an entry here before the runner
+/// can find it.
+pub const SOLVERS: [Solver; 7] = [
+    Solver::of::<Day1>(),
+    Solver::of::<Day2>(),
+    Solver::of::<Day3>(),
+    Solver::of::<Day4>(),
+    Solver::of::<Day5>(),
+    Solver::of::<Day6>(),
+    Solver::of::<Day7>(),
+];
+
+/// Type-erased handle on a [`Solved`] impl, so that Days can be looked up at
+/// runtime.
+pub struct Solver {
+    pub day: u8,
+    pub input_file_path: &'static str,
+    pub solution: fn(Part, &str) -> Result<Answer>,
+    pub solutions: fn(&[Part], &str) -> Result<PartAnswers>,
+    pub time_phases: fn(Part, &str) -> Result<PhaseTimings>,
+}
+
+impl Solver {
+    const fn of<T: Solved>() -> Self {
+        Self {
+            day: T::DAY,
+            input_file_path: T::INPUT_FILE_PATH,
+            solution: T::solution,
+            solutions: T::solutions,
+            time_phases: time_phases::<T>,
+        }
+    }
+}
+
+/// Looks up the solver for `day`, if there is one.
+pub fn find_solver(day: u8) -> Option<&'static Solver> {
+    SOLVERS.iter().find(|solver| solver.day == day)
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::problem::{Answer, InputSource, Part};
+use crate::registry::Solver;
+
+/// The recorded answers for every day's checked-in puzzle input.
+pub const ANSWERS_FILE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/answers.json");
+
+/// The known-good answer for one part of one day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedAnswer {
+    pub day:    u8,
+    pub part:   u8,
+    pub answer: Answer,
+}
+
+/// A set of recorded answers, as stored in an answers file.
+#[derive(Debug, Default)]
+pub struct RecordedAnswers(Vec<RecordedAnswer>);
+
+impl RecordedAnswers {
+    /// Loads answers from `path`; a missing file counts as no answers at all.
+    pub fn load(path: &str) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                let answers = serde_json::from_str(&contents)
+                    .context(anyhow!("Failed to parse answers file {}", path))?;
+                Ok(Self(answers))
+            },
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(anyhow!(err).context(anyhow!("Failed to read answers file {}", path))),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        let contents = serde_json::to_string_pretty(&self.0)?;
+        fs::write(path, contents + "\n").context(anyhow!("Failed to write answers file {}", path))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.0
+            .iter()
+            .find(|recorded| recorded.day == day && recorded.part == part.as_int())
+            .map(|recorded| &recorded.answer)
+    }
+
+    /// Records `answer`, replacing any answer already recorded for that part.
+    pub fn set(&mut self, day: u8, part: Part, answer: Answer) {
+        let part = part.as_int();
+        self.0.retain(|recorded| !(recorded.day == day && recorded.part == part));
+        self.0.push(RecordedAnswer { day, part, answer });
+        self.0.sort_by_key(|recorded| (recorded.day, recorded.part));
+    }
+}
+
+/// The outcome of checking one part's answer against the recorded one.
+#[derive(Debug)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer, actual: Answer },
+    Missing { actual: Answer },
+    Error(anyhow::Error),
+}
+
+impl Verdict {
+    pub fn is_pass(&self) -> bool {
+        matches!(self, Self::Pass)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail { expected, actual } => {
+                write!(f, "FAIL (expected {}, got {})", expected, actual)
+            },
+            Self::Missing { actual } => write!(f, "missing (got {})", actual),
+            Self::Error(err) => write!(f, "ERROR ({:#})", err),
+        }
+    }
+}
+
+/// Solves one part of a day from its checked-in input, and compares the
+/// result to the recorded answer.
+pub fn verify_solver(solver: &Solver, part: Part, recorded: &RecordedAnswers) -> Verdict {
+    let actual = match solve_default_input(solver, part) {
+        Ok(actual) => actual,
+        Err(err) => return Verdict::Error(err),
+    };
+    match recorded.get(solver.day, part) {
+        Some(expected) if *expected == actual => Verdict::Pass,
+        Some(expected) => Verdict::Fail { expected: expected.clone(), actual },
+        None => Verdict::Missing { actual },
+    }
+}
+
+fn solve_default_input(solver: &Solver, part: Part) -> Result<Answer> {
+    let input = InputSource::Default.read_to_string(solver.input_file_path)?;
+    (solver.solution)(part, &input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::find_solver;
+
+    #[test]
+    fn test_verdicts() {
+        let solver = find_solver(1).unwrap();
+        let mut recorded = RecordedAnswers::default();
+        assert!(matches!(
+            verify_solver(solver, Part::One, &recorded),
+            Verdict::Missing { .. }
+        ));
+
+        recorded.set(1, Part::One, Answer::Int(0));
+        assert!(matches!(
+            verify_solver(solver, Part::One, &recorded),
+            Verdict::Fail { .. }
+        ));
+
+        recorded.set(1, Part::One, Answer::Int(1014624));
+        assert!(verify_solver(solver, Part::One, &recorded).is_pass());
+    }
+}
@@ -0,0 +1,38 @@
+use std::fs;
+
+use advent2020::day1::{Day1, ExpenseAnalyzer};
+use advent2020::day3::{PathAnalyzer, Slope};
+use advent2020::day7::Bags;
+use advent2020::problem::{Answer, Part, Solved};
+use advent2020::registry::{find_solver, SOLVERS};
+
+#[test]
+fn test_solution_through_trait() {
+    let input = fs::read_to_string("src/day1/sample").unwrap();
+    let solution = Day1::solution(Part::One, &input).unwrap();
+    assert_eq!(solution, Answer::Int(514579));
+}
+
+#[test]
+fn test_domain_types() {
+    let expenses = ExpenseAnalyzer::new("1721\n979\n366\n299\n675\n1456").unwrap();
+    assert_eq!(expenses.find_summing_combination(2, 2020).unwrap(), vec![
+        299, 1721
+    ]);
+
+    let input = fs::read_to_string("src/day3/sample").unwrap();
+    let analyzer = PathAnalyzer::new(&input).unwrap();
+    let num_trees = analyzer.calculate_num_trees(Slope { tiles_right: 3, tiles_down: 1 });
+    assert_eq!(num_trees.unwrap(), 7);
+
+    let input = fs::read_to_string("src/day7/sample").unwrap();
+    let bags = Bags::new(&input).unwrap();
+    assert_eq!(bags.num_inner_bags("shiny gold"), 32);
+}
+
+#[test]
+fn test_registry() {
+    assert_eq!(SOLVERS.len(), 7);
+    assert_eq!(find_solver(4).unwrap().day, 4);
+    assert!(find_solver(25).is_none());
+}